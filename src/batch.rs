@@ -0,0 +1,400 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::ops::Range;
+use std::path::Path;
+
+use crate::{Intersection, Triangle};
+
+/*
+ * Every function here returning index pairs returns them in canonical form:
+ * each pair has i < j, the list is sorted and has no duplicates. Callers may
+ * rely on this, e.g. to compare results from different functions directly.
+ *
+ * Trivial inputs need no special casing: fewer than two triangles give no
+ * pairs, and two identical triangles are coplanar and overlapping, so they
+ * are reported as a pair like any other.
+ */
+
+/// All pairs `(i, j)` of triangles in `tris` that intersect, in canonical
+/// form: `i < j`, sorted, without duplicates.
+pub fn find_intersecting_pairs(tris: &[Triangle]) -> Vec<(usize, usize)> {
+    pairs_in_rows(tris, 0..tris.len(), |_| true)
+}
+
+/// Put pairs gathered some other way, e.g. from several threads, into the
+/// canonical form the batch functions return.
+pub fn canonicalize_pairs(pairs: &mut Vec<(usize, usize)>) {
+    for pair in pairs.iter_mut() {
+        *pair = (pair.0.min(pair.1), pair.0.max(pair.1));
+    }
+
+    pairs.sort_unstable();
+    pairs.dedup();
+}
+
+/// The same pairs as `find_intersecting_pairs`, computed lazily `chunk`
+/// values of `i` at a time so callers can report progress or stop between
+/// chunks. Concatenating every chunk gives the full result, so chunks come
+/// in canonical order too.
+///
+/// Panics if `chunk` is 0.
+pub fn find_intersecting_pairs_chunked(
+    tris: &[Triangle],
+    chunk: usize,
+) -> impl Iterator<Item = Vec<(usize, usize)>> + '_ {
+    assert!(chunk != 0, "chunk size must be non-zero");
+
+    (0..tris.len())
+        .step_by(chunk)
+        .map(move |start| pairs_in_rows(tris, start..tris.len().min(start + chunk), |_| true))
+}
+
+/// Intersecting pairs of a tessellated patch, ignoring the pairs listed in
+/// `adjacent` (in either order), which touch by construction, and pairs
+/// sharing a vertex (with identical coordinates) that only touch. What
+/// remains means the patch folds through itself, possibly through a shared
+/// vertex.
+pub fn detect_fold(tris: &[Triangle], adjacent: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let adjacent: HashSet<(usize, usize)> = adjacent
+        .iter()
+        .map(|&(i, j)| (i.min(j), i.max(j)))
+        .collect();
+
+    let bits = |t: &Triangle| t.vertices().map(|v| v.to_array().map(f64::to_bits));
+    let share_vertex = |i: usize, j: usize| {
+        let (a, b) = (bits(&tris[i]), bits(&tris[j]));
+        a.iter().any(|v| b.contains(v))
+    };
+
+    find_intersecting_pairs(tris)
+        .into_iter()
+        .filter(|&(i, j)| !adjacent.contains(&(i, j)))
+        .filter(|&(i, j)| {
+            !share_vertex(i, j)
+                || !matches!(
+                    tris[i].intersection_detail(&tris[j]),
+                    Some(Intersection::Touching(_))
+                )
+        })
+        .collect()
+}
+
+/// A triangle carrying caller data, such as an ID from a mesh file.
+#[derive(Clone, Debug)]
+pub struct TaggedTriangle<T> {
+    pub triangle: Triangle,
+    pub tag: T,
+}
+
+impl<T> TaggedTriangle<T> {
+    pub fn new(triangle: Triangle, tag: T) -> TaggedTriangle<T> {
+        TaggedTriangle { triangle, tag }
+    }
+}
+
+/// `find_intersecting_pairs` reporting the tags of each intersecting pair,
+/// in the same order as the index pairs would be.
+pub fn find_intersecting_tags<T>(tris: &[TaggedTriangle<T>]) -> Vec<(&T, &T)> {
+    let triangles: Vec<Triangle> = tris.iter().map(|t| t.triangle.clone()).collect();
+
+    find_intersecting_pairs(&triangles)
+        .into_iter()
+        .map(|(i, j)| (&tris[i].tag, &tris[j].tag))
+        .collect()
+}
+
+/// What the batch functions do with zero-area triangles.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Degenerate {
+    /// Test them like any other triangle.
+    #[default]
+    Include,
+    /// Leave them out of every pair.
+    Skip,
+}
+
+/// Pairs found by a batch run, plus the indices of the triangles skipped.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BatchResult {
+    pub pairs: Vec<(usize, usize)>,
+    pub skipped: Vec<usize>,
+}
+
+/// `find_intersecting_pairs` with a policy for degenerate triangles. Indices
+/// in the result still refer to `tris`.
+pub fn find_intersecting_pairs_with(tris: &[Triangle], degenerate: Degenerate) -> BatchResult {
+    let skip: Vec<bool> = tris
+        .iter()
+        .map(|t| degenerate == Degenerate::Skip && t.is_degenerate())
+        .collect();
+
+    BatchResult {
+        pairs: pairs_in_rows(tris, 0..tris.len(), |i| !skip[i]),
+        skipped: (0..tris.len()).filter(|&i| skip[i]).collect(),
+    }
+}
+
+fn pairs_in_rows(
+    tris: &[Triangle],
+    rows: Range<usize>,
+    include: impl Fn(usize) -> bool,
+) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+
+    for i in rows.filter(|&i| include(i)) {
+        for j in ((i + 1)..tris.len()).filter(|&j| include(j)) {
+            if tris[i].intersect(&tris[j]) {
+                pairs.push((i, j));
+            }
+        }
+    }
+
+    pairs
+}
+
+/// Write `pairs` to `path` as CSV, one `i,j` per line after an `i,j` header.
+pub fn write_pairs_csv(pairs: &[(usize, usize)], path: &Path) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+
+    writeln!(out, "i,j")?;
+    for (i, j) in pairs {
+        writeln!(out, "{},{}", i, j)?;
+    }
+
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jitter::XorShift;
+    use crate::{triangle, Scene, Vertex};
+
+    /* Small triangles scattered through a unit cube, so some cross */
+    fn scattered(n: usize) -> Vec<Triangle> {
+        let mut rng = XorShift(0x0bad_5eed_1234_5678);
+        let mut near =
+            |c: &Vertex, r: f64| c.add(&Vertex::new(rng.unit(), rng.unit(), rng.unit()).scale(r));
+
+        (0..n)
+            .map(|_| {
+                let c = near(&Vertex::new(0.0, 0.0, 0.0), 0.5);
+                Triangle::new(near(&c, 0.1), near(&c, 0.1), near(&c, 0.1))
+            })
+            .collect()
+    }
+
+    fn is_canonical(pairs: &[(usize, usize)]) -> bool {
+        pairs.iter().all(|&(i, j)| i < j) && pairs.windows(2).all(|w| w[0] < w[1])
+    }
+
+    #[test]
+    fn backends_agree_in_canonical_order() {
+        let tris = scattered(300);
+        let pairs = find_intersecting_pairs(&tris);
+        assert!(is_canonical(&pairs));
+        assert!(!pairs.is_empty());
+
+        for chunk in [1, 7, 300, 1000] {
+            let chunked: Vec<_> = find_intersecting_pairs_chunked(&tris, chunk)
+                .flatten()
+                .collect();
+            assert_eq!(chunked, pairs);
+        }
+
+        assert_eq!(
+            find_intersecting_pairs_with(&tris, Degenerate::Include).pairs,
+            pairs
+        );
+
+        let mut scene = Scene::new();
+        let mut incremental = Vec::new();
+        for (j, t) in tris.iter().enumerate() {
+            incremental.extend(scene.insert(t.clone()).into_iter().map(|i| (j, i)));
+        }
+        canonicalize_pairs(&mut incremental);
+        assert_eq!(incremental, pairs);
+    }
+
+    #[test]
+    fn canonicalize_orders_and_dedups() {
+        let mut pairs = vec![(3, 1), (0, 2), (1, 3), (2, 0), (0, 1)];
+        canonicalize_pairs(&mut pairs);
+        assert_eq!(pairs, vec![(0, 1), (0, 2), (1, 3)]);
+    }
+
+    #[test]
+    fn trivial_inputs() {
+        let t = Triangle::new(
+            Vertex::new(0.0, 0.0, 0.0),
+            Vertex::new(2.0, 1.0, 1.0),
+            Vertex::new(0.3, 2.0, -1.0),
+        );
+
+        for (tris, expected) in [
+            (vec![], vec![]),
+            (vec![t.clone()], vec![]),
+            (vec![t.clone(), t.clone()], vec![(0, 1)]),
+        ] {
+            assert_eq!(find_intersecting_pairs(&tris), expected);
+            assert_eq!(
+                find_intersecting_pairs_chunked(&tris, 1)
+                    .flatten()
+                    .collect::<Vec<_>>(),
+                expected
+            );
+
+            let result = find_intersecting_pairs_with(&tris, Degenerate::Skip);
+            assert_eq!(result.pairs, expected);
+            assert!(result.skipped.is_empty());
+
+            let mut scene = Scene::new();
+            let hits: Vec<Vec<usize>> = tris.iter().map(|t| scene.insert(t.clone())).collect();
+            assert_eq!(hits.concat().len(), expected.len());
+        }
+    }
+
+    /*
+     * A strip of quads along two rails at y = 0 and y = 1, each split in two
+     * triangles, with rail point k at x = k. From k = 3 onward it folds back
+     * over itself and down through z = 0 when `folded`.
+     */
+    fn strip(folded: bool) -> Vec<Triangle> {
+        let point = |k: usize, y: f64| {
+            let k = k as f64;
+            if folded && k > 3.0 {
+                Vertex::new(
+                    3.0 - (k - 3.0) * 0.8,
+                    y,
+                    0.6 * (k - 3.0) - 0.5 * (k - 3.0).powi(2),
+                )
+            } else {
+                Vertex::new(k, y, 0.0)
+            }
+        };
+
+        (0..6)
+            .flat_map(|k| {
+                let (b0, b1, t0, t1) = (
+                    point(k, 0.0),
+                    point(k + 1, 0.0),
+                    point(k, 1.0),
+                    point(k + 1, 1.0),
+                );
+                [Triangle::new(b0, b1, t0), Triangle::new(b1, t1, t0)]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn detect_fold_in_strip() {
+        let adjacent: Vec<(usize, usize)> = (0..11).map(|i| (i, i + 1)).collect();
+
+        // Faces two apart share only a vertex, which isn't a fold
+        assert_eq!(detect_fold(&strip(false), &adjacent), vec![]);
+
+        // Only faces from before the fold can meet faces after it
+        let folds = detect_fold(&strip(true), &adjacent);
+        assert!(!folds.is_empty());
+        assert!(folds.iter().all(|&(i, j)| i < 6 && j >= 8), "{:?}", folds);
+    }
+
+    #[test]
+    fn detect_fold_through_shared_vertex() {
+        // A fan around the origin whose last face swings back down through
+        // the first, crossing it from the shared vertex to (0.35, 0.35, 0)
+        let o = Vertex::new(0.0, 0.0, 0.0);
+        let rim = [
+            Vertex::new(1.0, 0.0, 0.0),
+            Vertex::new(0.0, 1.0, 0.0),
+            Vertex::new(-1.0, 0.3, 0.2),
+            Vertex::new(0.5, 0.2, 1.0),
+            Vertex::new(0.2, 0.5, -1.0),
+        ];
+        let mut tris: Vec<Triangle> = (0..4)
+            .map(|k| Triangle::new(o, rim[k], rim[k + 1]))
+            .collect();
+        let adjacent = [(0, 1), (1, 2), (2, 3)];
+
+        assert!(matches!(
+            tris[0].intersection_detail(&tris[3]),
+            Some(Intersection::Crossing(_))
+        ));
+        assert_eq!(detect_fold(&tris, &adjacent), vec![(0, 3)]);
+
+        // Lifted clear of the first face, it only shares the vertex
+        tris[3] = Triangle::new(o, rim[3], Vertex::new(0.2, 0.5, 1.0));
+        assert_eq!(detect_fold(&tris, &adjacent), vec![]);
+    }
+
+    #[test]
+    fn csv_write_then_read() {
+        let pairs = find_intersecting_pairs(&scattered(100));
+        assert!(!pairs.is_empty());
+
+        let path = std::env::temp_dir().join(format!(
+            "triangle-intersect-{}-pairs.csv",
+            std::process::id()
+        ));
+        write_pairs_csv(&pairs, &path).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let mut lines = text.lines();
+        assert_eq!(lines.next(), Some("i,j"));
+        let read: Vec<(usize, usize)> = lines
+            .map(|line| {
+                let (i, j) = line.split_once(',').unwrap();
+                (i.parse().unwrap(), j.parse().unwrap())
+            })
+            .collect();
+        assert_eq!(read, pairs);
+    }
+
+    #[test]
+    fn degenerate_face_skipped() {
+        let tris = [
+            triangle![[0, 0, 0], [4, 0, 0], [0, 4, 0]],
+            // Collinear, piercing both of the others
+            triangle![[0, 0, -1], [1, 1, 1], [2, 2, 3]],
+            triangle![[1, 0, -1], [1, 0, 3], [1, 6, 0]],
+        ];
+        assert!(tris[1].is_degenerate());
+
+        assert_eq!(
+            find_intersecting_pairs_with(&tris, Degenerate::Skip),
+            BatchResult {
+                pairs: vec![(0, 2)],
+                skipped: vec![1],
+            }
+        );
+        assert_eq!(
+            find_intersecting_pairs_with(&tris, Degenerate::Include),
+            BatchResult {
+                pairs: vec![(0, 1), (0, 2), (1, 2)],
+                skipped: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn tags_of_intersecting_pairs() {
+        let tris = vec![
+            TaggedTriangle::new(triangle![[0, 0, 0], [4, 0, 0], [0, 4, 0]], "floor"),
+            TaggedTriangle::new(triangle![[5, 5, 5], [6, 5, 5], [5, 6, 5]], "far"),
+            TaggedTriangle::new(triangle![[1, 0, -1], [1, 0, 3], [1, 6, 0]], "wall"),
+            TaggedTriangle::new(triangle![[0, 1, -1], [0, 1, 3], [6, 1, 0]], "fence"),
+        ];
+
+        assert_eq!(
+            find_intersecting_tags(&tris),
+            vec![
+                (&"floor", &"wall"),
+                (&"floor", &"fence"),
+                (&"wall", &"fence")
+            ]
+        );
+        assert!(find_intersecting_tags::<&str>(&[]).is_empty());
+    }
+}
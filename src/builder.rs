@@ -0,0 +1,177 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::{Triangle, Vertex};
+
+/// A problem `TriangleBuilder::build` found with the winding of a face.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WindingWarning {
+    /// The face is wound against the majority of its connected surface.
+    Flipped(usize),
+    /// The face's neighbors need it wound both ways, so its connected
+    /// surface can't be oriented consistently at all (e.g. a Moebius strip).
+    NotOrientable(usize),
+}
+
+/// Collects triangles for a closed or open surface and checks, on `build`,
+/// that faces sharing an edge traverse it in opposite directions. Edges are
+/// matched by exact vertex coordinates.
+#[derive(Default)]
+pub struct TriangleBuilder {
+    triangles: Vec<Triangle>,
+}
+
+type Key = [u64; 3];
+
+fn key(v: &Vertex) -> Key {
+    // Adding 0.0 turns -0.0 into 0.0 so both match
+    [v.x, v.y, v.z].map(|c| (c + 0.0).to_bits())
+}
+
+impl TriangleBuilder {
+    pub fn new() -> TriangleBuilder {
+        TriangleBuilder::default()
+    }
+
+    pub fn push(&mut self, t: Triangle) -> &mut TriangleBuilder {
+        self.triangles.push(t);
+        self
+    }
+
+    /// The triangles with warnings for every face whose winding doesn't fit
+    /// its neighbors, ordered by face index.
+    pub fn build(self) -> (Vec<Triangle>, Vec<WindingWarning>) {
+        // Faces on each undirected edge, with whether they run it forwards
+        let mut edges: HashMap<(Key, Key), Vec<(usize, bool)>> = HashMap::new();
+        for (f, t) in self.triangles.iter().enumerate() {
+            for e in t.edges() {
+                let (a, b) = (key(&e.vertices[0]), key(&e.vertices[1]));
+                edges
+                    .entry((a.min(b), a.max(b)))
+                    .or_default()
+                    .push((f, a < b));
+            }
+        }
+
+        let mut neighbors = vec![Vec::new(); self.triangles.len()];
+        for faces in edges.values() {
+            for (i, &(f, f_fwd)) in faces.iter().enumerate() {
+                for &(g, g_fwd) in &faces[(i + 1)..] {
+                    // Consistent neighbors run the shared edge opposite ways,
+                    // so g needs flipping relative to f when they agree
+                    neighbors[f].push((g, f_fwd == g_fwd));
+                    neighbors[g].push((f, f_fwd == g_fwd));
+                }
+            }
+        }
+
+        let mut flip: Vec<Option<bool>> = vec![None; self.triangles.len()];
+        let mut warnings = Vec::new();
+
+        for root in 0..self.triangles.len() {
+            if flip[root].is_some() {
+                continue;
+            }
+
+            // Orient the connected surface relative to its first face
+            flip[root] = Some(false);
+            let mut component = vec![root];
+            let mut conflicts = Vec::new();
+            let mut queue = VecDeque::from([root]);
+
+            while let Some(f) = queue.pop_front() {
+                let f_flip = flip[f].unwrap_or_default();
+                for &(g, differ) in &neighbors[f] {
+                    let want = f_flip != differ;
+                    match flip[g] {
+                        None => {
+                            flip[g] = Some(want);
+                            component.push(g);
+                            queue.push_back(g);
+                        }
+                        Some(have) if have != want => conflicts.push(g),
+                        Some(_) => (),
+                    }
+                }
+            }
+
+            if !conflicts.is_empty() {
+                conflicts.sort_unstable();
+                conflicts.dedup();
+                warnings.extend(conflicts.into_iter().map(WindingWarning::NotOrientable));
+                continue;
+            }
+
+            // Blame whichever orientation is in the minority
+            let flipped = component.iter().filter(|&&f| flip[f] == Some(true)).count();
+            let flipped_majority = flipped * 2 > component.len();
+            warnings.extend(
+                component
+                    .into_iter()
+                    .filter(|&f| flip[f] == Some(!flipped_majority))
+                    .map(WindingWarning::Flipped),
+            );
+        }
+
+        warnings.sort_unstable_by_key(|w| match *w {
+            WindingWarning::Flipped(f) | WindingWarning::NotOrientable(f) => f,
+        });
+
+        (self.triangles, warnings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(points: &[Vertex], faces: &[[usize; 3]]) -> Vec<WindingWarning> {
+        let mut builder = TriangleBuilder::new();
+        for &[u, v, w] in faces {
+            builder.push(Triangle::new(points[u], points[v], points[w]));
+        }
+
+        let (triangles, warnings) = builder.build();
+        assert_eq!(triangles.len(), faces.len());
+        warnings
+    }
+
+    #[test]
+    fn one_flipped_face() {
+        let points = [
+            Vertex::new(0.0, 0.0, 0.0),
+            Vertex::new(1.0, 0.0, 0.0),
+            Vertex::new(0.0, 1.0, 0.0),
+            Vertex::new(0.0, 0.0, 1.0),
+        ];
+        let mut faces = vec![[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]];
+        assert_eq!(build(&points, &faces), vec![]);
+
+        faces[2] = [0, 2, 3];
+        assert_eq!(build(&points, &faces), vec![WindingWarning::Flipped(2)]);
+
+        // A separate, consistent patch doesn't change the verdict
+        let mut points = points.to_vec();
+        points.extend([5.0, 6.0, 7.0].map(|x| Vertex::new(x, x * x, 0.0)));
+        faces.push([4, 5, 6]);
+        assert_eq!(build(&points, &faces), vec![WindingWarning::Flipped(2)]);
+    }
+
+    #[test]
+    fn moebius_strip() {
+        // The five-vertex Moebius band: each face shares an edge with the next
+        // and the last wraps around to the first with a half twist
+        let points: Vec<Vertex> = (0..5)
+            .map(|k| {
+                let a = std::f64::consts::TAU * k as f64 / 5.0;
+                Vertex::new(a.cos(), a.sin(), (k % 2) as f64)
+            })
+            .collect();
+        let faces = [[0, 1, 2], [1, 2, 3], [2, 3, 4], [3, 4, 0], [4, 0, 1]];
+
+        let warnings = build(&points, &faces);
+        assert!(!warnings.is_empty());
+        assert!(warnings
+            .iter()
+            .all(|w| matches!(w, WindingWarning::NotOrientable(_))));
+    }
+}
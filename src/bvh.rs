@@ -0,0 +1,403 @@
+/*
+ * Incremental bounding volume hierarchy: a binary tree of AABBs grown one
+ * leaf at a time, after Box2D's dynamic tree. Each insert picks the sibling
+ * by surface area heuristic, then walks back to the root refitting boxes
+ * and rotating wherever one subtree is more than one level deeper than the
+ * other, so the height stays O(log n).
+ *
+ * Link: https://box2d.org/files/ErinCatto_DynamicBVH_GDC2019.pdf
+ */
+
+use std::io::{self, Read, Write};
+
+use crate::{Aabb, Vertex};
+
+fn union(a: &Aabb, b: &Aabb) -> Aabb {
+    Aabb {
+        min: Vertex::new(
+            a.min.x.min(b.min.x),
+            a.min.y.min(b.min.y),
+            a.min.z.min(b.min.z),
+        ),
+        max: Vertex::new(
+            a.max.x.max(b.max.x),
+            a.max.y.max(b.max.y),
+            a.max.z.max(b.max.z),
+        ),
+    }
+}
+
+fn surface_area(a: &Aabb) -> f64 {
+    let d = a.max.subtract(&a.min);
+    2.0 * (d.x * d.y + d.y * d.z + d.z * d.x)
+}
+
+struct Node {
+    aabb: Aabb,
+    parent: Option<usize>,
+    /* None for a leaf */
+    children: Option<[usize; 2]>,
+    /* The caller's index, for leaves */
+    item: usize,
+    /* 0 for a leaf */
+    height: usize,
+}
+
+#[derive(Default)]
+pub(crate) struct Bvh {
+    nodes: Vec<Node>,
+    root: Option<usize>,
+}
+
+impl Bvh {
+    /* Add `item` with bounding box `aabb`, in O(log n) */
+    pub(crate) fn insert(&mut self, aabb: Aabb, item: usize) {
+        let leaf = self.nodes.len();
+        self.nodes.push(Node {
+            aabb,
+            parent: None,
+            children: None,
+            item,
+            height: 0,
+        });
+
+        let root = match self.root {
+            Some(root) => root,
+            None => {
+                self.root = Some(leaf);
+                return;
+            }
+        };
+
+        let sibling = self.best_sibling(root, &aabb);
+
+        // A new branch takes the sibling's place, with the sibling and the
+        // leaf as its children
+        let old_parent = self.nodes[sibling].parent;
+        let branch = self.nodes.len();
+        self.nodes.push(Node {
+            aabb: union(&self.nodes[sibling].aabb, &aabb),
+            parent: old_parent,
+            children: Some([sibling, leaf]),
+            item: 0,
+            height: self.nodes[sibling].height + 1,
+        });
+        self.replace_child(old_parent, sibling, branch);
+        self.nodes[sibling].parent = Some(branch);
+        self.nodes[leaf].parent = Some(branch);
+
+        let mut index = Some(branch);
+        while let Some(i) = index {
+            let i = self.balance(i);
+            self.refit(i);
+            index = self.nodes[i].parent;
+        }
+    }
+
+    /* Indices of the items whose boxes overlap `aabb`, in no particular order */
+    pub(crate) fn query(&self, aabb: &Aabb) -> Vec<usize> {
+        let mut items = Vec::new();
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+
+        while let Some(i) = stack.pop() {
+            let node = &self.nodes[i];
+            if !node.aabb.overlaps(aabb) {
+                continue;
+            }
+
+            match node.children {
+                Some(children) => stack.extend(children),
+                None => items.push(node.item),
+            }
+        }
+
+        items
+    }
+
+    /*
+     * Little endian, after the node count: the root, then per node its box
+     * as six f64, its parent, both children, its item and its height as
+     * u64, with u64::MAX standing for None. Writing the nodes as they are,
+     * rather than rebuilding on read, keeps query results identical.
+     */
+    pub(crate) fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        let index = |i: Option<usize>| i.map_or(u64::MAX, |i| i as u64);
+
+        out.write_all(&(self.nodes.len() as u64).to_le_bytes())?;
+        out.write_all(&index(self.root).to_le_bytes())?;
+
+        for node in &self.nodes {
+            for v in [&node.aabb.min, &node.aabb.max] {
+                for c in v.to_array() {
+                    out.write_all(&c.to_le_bytes())?;
+                }
+            }
+
+            let [a, b] = match node.children {
+                Some([a, b]) => [Some(a), Some(b)],
+                None => [None, None],
+            };
+            for i in [index(node.parent), index(a), index(b)] {
+                out.write_all(&i.to_le_bytes())?;
+            }
+            out.write_all(&(node.item as u64).to_le_bytes())?;
+            out.write_all(&(node.height as u64).to_le_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    /*
+     * Read what `write` wrote for `items` items, checking that it is a tree
+     * holding each of them once
+     */
+    pub(crate) fn read(r: &mut impl Read, items: usize) -> io::Result<Bvh> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "BVH: index out of range");
+        let mut u64_at = || -> io::Result<u64> {
+            let mut bytes = [0; 8];
+            r.read_exact(&mut bytes)?;
+            Ok(u64::from_le_bytes(bytes))
+        };
+
+        let count = u64_at()? as usize;
+        let index = |i: u64| match i {
+            u64::MAX => Ok(None),
+            i if (i as usize) < count => Ok(Some(i as usize)),
+            _ => Err(invalid()),
+        };
+        let root = index(u64_at()?)?;
+
+        let mut nodes = Vec::with_capacity(count.min(1 << 20));
+        for _ in 0..count {
+            let mut c = [0.0; 6];
+            for c in &mut c {
+                *c = f64::from_bits(u64_at()?);
+            }
+            let aabb = Aabb {
+                min: Vertex::new(c[0], c[1], c[2]),
+                max: Vertex::new(c[3], c[4], c[5]),
+            };
+
+            let parent = index(u64_at()?)?;
+            let children = match (index(u64_at()?)?, index(u64_at()?)?) {
+                (Some(a), Some(b)) => Some([a, b]),
+                (None, None) => None,
+                _ => return Err(invalid()),
+            };
+            let item = u64_at()? as usize;
+
+            nodes.push(Node {
+                aabb,
+                parent,
+                children,
+                item,
+                height: u64_at()? as usize,
+            });
+        }
+
+        // Walk down from the root, which must reach every node exactly once,
+        // with each child naming its parent, so queries on a corrupt file can't
+        // loop or miss an item
+        let mut seen = vec![false; count];
+        let mut stack: Vec<(usize, Option<usize>)> = root.map(|r| (r, None)).into_iter().collect();
+        while let Some((i, parent)) = stack.pop() {
+            if seen[i] || nodes[i].parent != parent {
+                return Err(invalid());
+            }
+            seen[i] = true;
+
+            if let Some(children) = nodes[i].children {
+                stack.extend(children.map(|c| (c, Some(i))));
+            }
+        }
+
+        // Every item in a leaf of its own
+        let mut leaves: Vec<usize> = nodes
+            .iter()
+            .filter(|node| node.children.is_none())
+            .map(|node| node.item)
+            .collect();
+        leaves.sort_unstable();
+        if seen.contains(&false) || !leaves.iter().copied().eq(0..items) {
+            return Err(invalid());
+        }
+
+        Ok(Bvh { nodes, root })
+    }
+
+    /*
+     * Descend from the root while some child would make a cheaper sibling
+     * than the current node, counting the growth of every box on the way.
+     */
+    fn best_sibling(&self, root: usize, aabb: &Aabb) -> usize {
+        let mut index = root;
+
+        while let Some(children) = self.nodes[index].children {
+            let area = surface_area(&self.nodes[index].aabb);
+            let combined = surface_area(&union(&self.nodes[index].aabb, aabb));
+
+            // Pairing with this node creates a parent of the combined size,
+            // and going lower grows this node to it anyway
+            let cost = 2.0 * combined;
+            let inheritance = 2.0 * (combined - area);
+
+            let descend_cost = |child: usize| {
+                let node = &self.nodes[child];
+                let grown = surface_area(&union(&node.aabb, aabb));
+                match node.children {
+                    Some(_) => grown - surface_area(&node.aabb) + inheritance,
+                    None => grown + inheritance,
+                }
+            };
+            let costs = children.map(descend_cost);
+
+            if cost < costs[0] && cost < costs[1] {
+                break;
+            }
+
+            index = if costs[0] < costs[1] {
+                children[0]
+            } else {
+                children[1]
+            };
+        }
+
+        index
+    }
+
+    fn replace_child(&mut self, parent: Option<usize>, old: usize, new: usize) {
+        match parent {
+            Some(p) => {
+                let children = self.nodes[p].children.as_mut().unwrap();
+                let slot = if children[0] == old { 0 } else { 1 };
+                children[slot] = new;
+            }
+            None => self.root = Some(new),
+        }
+    }
+
+    fn refit(&mut self, i: usize) {
+        if let Some([a, b]) = self.nodes[i].children {
+            self.nodes[i].aabb = union(&self.nodes[a].aabb, &self.nodes[b].aabb);
+            self.nodes[i].height = 1 + self.nodes[a].height.max(self.nodes[b].height);
+        }
+    }
+
+    /*
+     * If one child of branch `a` is more than one level deeper than the
+     * other, rotate that child up into a's place, as in Box2D's
+     * b2DynamicTree::Balance. Returns the index now at a's position.
+     */
+    fn balance(&mut self, a: usize) -> usize {
+        let [b, c] = match self.nodes[a].children {
+            Some(children) if self.nodes[a].height >= 2 => children,
+            _ => return a,
+        };
+
+        let (up, slot) = match self.nodes[c].height as isize - self.nodes[b].height as isize {
+            d if d > 1 => (c, 1),
+            d if d < -1 => (b, 0),
+            _ => return a,
+        };
+
+        // `up` takes a's place, keeps its taller child and gives `a` the
+        // shorter one in its own old slot
+        let [f, g] = self.nodes[up].children.unwrap();
+        let (keep, give) = if self.nodes[f].height > self.nodes[g].height {
+            (f, g)
+        } else {
+            (g, f)
+        };
+
+        let parent = self.nodes[a].parent;
+        self.replace_child(parent, a, up);
+        self.nodes[up].parent = parent;
+        self.nodes[up].children = Some([a, keep]);
+        self.nodes[a].parent = Some(up);
+
+        self.nodes[a].children.as_mut().unwrap()[slot] = give;
+        self.nodes[give].parent = Some(a);
+
+        self.refit(a);
+        self.refit(up);
+
+        up
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jitter::XorShift;
+
+    fn random_boxes(n: usize) -> Vec<Aabb> {
+        let mut rng = XorShift(0x9e37_79b9_7f4a_7c15);
+        (0..n)
+            .map(|_| {
+                let min = Vertex::new(rng.unit(), rng.unit(), rng.unit()).scale(10.0);
+                let size = Vertex::new(rng.unit(), rng.unit(), rng.unit())
+                    .add(&Vertex::new(1.0, 1.0, 1.0));
+                Aabb {
+                    min,
+                    max: min.add(&size.scale(0.5)),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn query_matches_brute_force() {
+        let boxes = random_boxes(500);
+        let mut bvh = Bvh::default();
+        assert!(bvh.query(&boxes[0]).is_empty());
+
+        for (i, aabb) in boxes.iter().enumerate() {
+            let mut found = bvh.query(aabb);
+            found.sort_unstable();
+            let expected: Vec<usize> = (0..i).filter(|&j| boxes[j].overlaps(aabb)).collect();
+            assert_eq!(found, expected);
+
+            bvh.insert(*aabb, i);
+        }
+    }
+
+    #[test]
+    fn stays_balanced() {
+        let mut bvh = Bvh::default();
+
+        // Boxes in a line are the worst case for an unbalanced tree
+        for i in 0..1024 {
+            let min = Vertex::new(i as f64, 0.0, 0.0);
+            let aabb = Aabb {
+                min,
+                max: min.add(&Vertex::new(0.5, 0.5, 0.5)),
+            };
+            bvh.insert(aabb, i);
+        }
+
+        let height = bvh.nodes[bvh.root.unwrap()].height;
+        assert!(height <= 20, "height {}", height);
+    }
+
+    #[test]
+    fn write_read_round_trip() {
+        let boxes = random_boxes(100);
+        let mut bvh = Bvh::default();
+        for (i, aabb) in boxes.iter().enumerate() {
+            bvh.insert(*aabb, i);
+        }
+
+        let mut bytes = Vec::new();
+        bvh.write(&mut bytes).unwrap();
+        let read = Bvh::read(&mut bytes.as_slice(), boxes.len()).unwrap();
+        for aabb in &boxes {
+            assert_eq!(read.query(aabb), bvh.query(aabb));
+        }
+
+        // Too many items, or node 0 claiming the wrong parent
+        assert!(Bvh::read(&mut bytes.as_slice(), boxes.len() + 1).is_err());
+        let parent = 16 + 48;
+        bytes[parent..parent + 8].copy_from_slice(&5u64.to_le_bytes());
+        assert!(Bvh::read(&mut bytes.as_slice(), boxes.len()).is_err());
+    }
+}
@@ -0,0 +1,373 @@
+/*
+ * Faithful port of the Devillers-Guigue triangle-triangle overlap test,
+ * including its coplanar sub-test. Touching counts as overlapping.
+ *
+ * Link: https://hal.inria.fr/inria-00072100/document
+ */
+
+use crate::planar::{self, orient, Point2};
+use crate::{Triangle, Vertex};
+
+/* Do the intervals cut by the two triangles on the planes' line overlap? */
+fn check_min_max(
+    p1: &Vertex,
+    q1: &Vertex,
+    r1: &Vertex,
+    p2: &Vertex,
+    q2: &Vertex,
+    r2: &Vertex,
+) -> bool {
+    let n = p2.subtract(q1).cross_product(&p1.subtract(q1));
+    if q2.subtract(q1).dot_product(&n) > 0.0 {
+        return false;
+    }
+
+    let n = p2.subtract(p1).cross_product(&r1.subtract(p1));
+    r2.subtract(p1).dot_product(&n) <= 0.0
+}
+
+/*
+ * With T1 permuted so p1 is alone on its side of T2's plane, permute T2 so
+ * p2 is alone on its side of T1's plane, with both in the orientation
+ * check_min_max expects.
+ */
+#[allow(clippy::too_many_arguments)]
+fn tri_tri_3d(
+    p1: &Vertex,
+    q1: &Vertex,
+    r1: &Vertex,
+    p2: &Vertex,
+    q2: &Vertex,
+    r2: &Vertex,
+    dp2: f64,
+    dq2: f64,
+    dr2: f64,
+    n1: &Vertex,
+) -> bool {
+    if dp2 > 0.0 {
+        if dq2 > 0.0 {
+            check_min_max(p1, r1, q1, r2, p2, q2)
+        } else if dr2 > 0.0 {
+            check_min_max(p1, r1, q1, q2, r2, p2)
+        } else {
+            check_min_max(p1, q1, r1, p2, q2, r2)
+        }
+    } else if dp2 < 0.0 {
+        if dq2 < 0.0 {
+            check_min_max(p1, q1, r1, r2, p2, q2)
+        } else if dr2 < 0.0 {
+            check_min_max(p1, q1, r1, q2, r2, p2)
+        } else {
+            check_min_max(p1, r1, q1, p2, q2, r2)
+        }
+    } else if dq2 < 0.0 {
+        if dr2 >= 0.0 {
+            check_min_max(p1, r1, q1, q2, r2, p2)
+        } else {
+            check_min_max(p1, q1, r1, p2, q2, r2)
+        }
+    } else if dq2 > 0.0 {
+        if dr2 > 0.0 {
+            check_min_max(p1, r1, q1, p2, q2, r2)
+        } else {
+            check_min_max(p1, q1, r1, q2, r2, p2)
+        }
+    } else if dr2 > 0.0 {
+        check_min_max(p1, q1, r1, r2, p2, q2)
+    } else if dr2 < 0.0 {
+        check_min_max(p1, r1, q1, r2, p2, q2)
+    } else {
+        coplanar_tri_tri(p1, q1, r1, p2, q2, r2, n1)
+    }
+}
+
+fn coplanar_tri_tri(
+    p1: &Vertex,
+    q1: &Vertex,
+    r1: &Vertex,
+    p2: &Vertex,
+    q2: &Vertex,
+    r2: &Vertex,
+    n1: &Vertex,
+) -> bool {
+    // Drop the axis the projection's area depends on most; the 2D test
+    // fixes up the orientation itself
+    let axis = planar::dominant_axis(n1);
+    let [p1, q1, r1, p2, q2, r2] = [p1, q1, r1, p2, q2, r2].map(|v| planar::project(v, axis));
+
+    tri_tri_overlap_2d(p1, q1, r1, p2, q2, r2)
+}
+
+fn tri_tri_overlap_2d(
+    p1: Point2,
+    q1: Point2,
+    r1: Point2,
+    p2: Point2,
+    q2: Point2,
+    r2: Point2,
+) -> bool {
+    if orient(p1, q1, r1) < 0.0 {
+        if orient(p2, q2, r2) < 0.0 {
+            ccw_tri_tri_2d(p1, r1, q1, p2, r2, q2)
+        } else {
+            ccw_tri_tri_2d(p1, r1, q1, p2, q2, r2)
+        }
+    } else if orient(p2, q2, r2) < 0.0 {
+        ccw_tri_tri_2d(p1, q1, r1, p2, r2, q2)
+    } else {
+        ccw_tri_tri_2d(p1, q1, r1, p2, q2, r2)
+    }
+}
+
+/* Both triangles counterclockwise; classify p1 against T2's edge lines */
+fn ccw_tri_tri_2d(p1: Point2, q1: Point2, r1: Point2, p2: Point2, q2: Point2, r2: Point2) -> bool {
+    if orient(p2, q2, p1) >= 0.0 {
+        if orient(q2, r2, p1) >= 0.0 {
+            if orient(r2, p2, p1) >= 0.0 {
+                true
+            } else {
+                intersection_test_edge(p1, q1, r1, p2, q2, r2)
+            }
+        } else if orient(r2, p2, p1) >= 0.0 {
+            intersection_test_edge(p1, q1, r1, r2, p2, q2)
+        } else {
+            intersection_test_vertex(p1, q1, r1, p2, q2, r2)
+        }
+    } else if orient(q2, r2, p1) >= 0.0 {
+        if orient(r2, p2, p1) >= 0.0 {
+            intersection_test_edge(p1, q1, r1, q2, r2, p2)
+        } else {
+            intersection_test_vertex(p1, q1, r1, q2, r2, p2)
+        }
+    } else {
+        intersection_test_vertex(p1, q1, r1, r2, p2, q2)
+    }
+}
+
+/* p1 lies in the region facing the vertex p2 of T2 */
+fn intersection_test_vertex(
+    p1: Point2,
+    q1: Point2,
+    r1: Point2,
+    p2: Point2,
+    q2: Point2,
+    r2: Point2,
+) -> bool {
+    if orient(r2, p2, q1) >= 0.0 {
+        if orient(r2, q2, q1) <= 0.0 {
+            if orient(p1, p2, q1) > 0.0 {
+                orient(p1, q2, q1) <= 0.0
+            } else {
+                orient(p1, p2, r1) >= 0.0 && orient(q1, r1, p2) >= 0.0
+            }
+        } else {
+            orient(p1, q2, q1) <= 0.0 && orient(r2, q2, r1) <= 0.0 && orient(q1, r1, q2) >= 0.0
+        }
+    } else if orient(r2, p2, r1) >= 0.0 {
+        if orient(q1, r1, r2) >= 0.0 {
+            orient(p1, p2, r1) >= 0.0
+        } else {
+            orient(q1, r1, q2) >= 0.0 && orient(r2, r1, q2) >= 0.0
+        }
+    } else {
+        false
+    }
+}
+
+/* p1 lies in the region facing the edge p2 q2 of T2 */
+fn intersection_test_edge(
+    p1: Point2,
+    q1: Point2,
+    r1: Point2,
+    p2: Point2,
+    _q2: Point2,
+    r2: Point2,
+) -> bool {
+    if orient(r2, p2, q1) >= 0.0 {
+        if orient(p1, p2, q1) >= 0.0 {
+            orient(p1, q1, r2) >= 0.0
+        } else {
+            orient(q1, r1, p2) >= 0.0 && orient(r1, p1, p2) >= 0.0
+        }
+    } else if orient(r2, p2, r1) >= 0.0 {
+        orient(p1, p2, r1) >= 0.0 && (orient(p1, r1, r2) >= 0.0 || orient(q1, r1, r2) >= 0.0)
+    } else {
+        false
+    }
+}
+
+impl Triangle {
+    /// Devillers-Guigue triangle-triangle overlap test. Touching and coplanar
+    /// overlapping triangles count as intersecting. Coplanarity is decided
+    /// exactly, with no tolerance.
+    pub fn intersect_dg(&self, t: &Triangle) -> bool {
+        let [p1, q1, r1] = &self.vertices;
+        let [p2, q2, r2] = &t.vertices;
+
+        // Signs of T1's vertices against T2's plane
+        let n2 = p2.subtract(r2).cross_product(&q2.subtract(r2));
+        let dp1 = p1.subtract(r2).dot_product(&n2);
+        let dq1 = q1.subtract(r2).dot_product(&n2);
+        let dr1 = r1.subtract(r2).dot_product(&n2);
+
+        if dp1 * dq1 > 0.0 && dp1 * dr1 > 0.0 {
+            return false;
+        }
+
+        // Signs of T2's vertices against T1's plane
+        let n1 = q1.subtract(p1).cross_product(&r1.subtract(p1));
+        let dp2 = p2.subtract(r1).dot_product(&n1);
+        let dq2 = q2.subtract(r1).dot_product(&n1);
+        let dr2 = r2.subtract(r1).dot_product(&n1);
+
+        if dp2 * dq2 > 0.0 && dp2 * dr2 > 0.0 {
+            return false;
+        }
+
+        // Permute T1 into the canonical form where p1 is alone on its side
+        let n1 = &n1;
+        if dp1 > 0.0 {
+            if dq1 > 0.0 {
+                tri_tri_3d(r1, p1, q1, p2, r2, q2, dp2, dr2, dq2, n1)
+            } else if dr1 > 0.0 {
+                tri_tri_3d(q1, r1, p1, p2, r2, q2, dp2, dr2, dq2, n1)
+            } else {
+                tri_tri_3d(p1, q1, r1, p2, q2, r2, dp2, dq2, dr2, n1)
+            }
+        } else if dp1 < 0.0 {
+            if dq1 < 0.0 {
+                tri_tri_3d(r1, p1, q1, p2, q2, r2, dp2, dq2, dr2, n1)
+            } else if dr1 < 0.0 {
+                tri_tri_3d(q1, r1, p1, p2, q2, r2, dp2, dq2, dr2, n1)
+            } else {
+                tri_tri_3d(p1, q1, r1, p2, r2, q2, dp2, dr2, dq2, n1)
+            }
+        } else if dq1 < 0.0 {
+            if dr1 >= 0.0 {
+                tri_tri_3d(q1, r1, p1, p2, r2, q2, dp2, dr2, dq2, n1)
+            } else {
+                tri_tri_3d(p1, q1, r1, p2, q2, r2, dp2, dq2, dr2, n1)
+            }
+        } else if dq1 > 0.0 {
+            if dr1 > 0.0 {
+                tri_tri_3d(p1, q1, r1, p2, r2, q2, dp2, dr2, dq2, n1)
+            } else {
+                tri_tri_3d(q1, r1, p1, p2, q2, r2, dp2, dq2, dr2, n1)
+            }
+        } else if dr1 > 0.0 {
+            tri_tri_3d(r1, p1, q1, p2, q2, r2, dp2, dq2, dr2, n1)
+        } else if dr1 < 0.0 {
+            tri_tri_3d(r1, p1, q1, p2, r2, q2, dp2, dr2, dq2, n1)
+        } else {
+            coplanar_tri_tri(p1, q1, r1, p2, q2, r2, n1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::orderings;
+    use crate::{triangle, Triangle};
+
+    /*
+     * The configurations the paper's case analysis separates, each checked
+     * for every vertex ordering of both triangles, in both argument orders
+     * and against intersect.
+     */
+    fn assert_dg(a: &Triangle, b: &Triangle, expected: bool) {
+        for a in &orderings(a) {
+            for b in &orderings(b) {
+                assert_eq!(a.intersect_dg(b), expected, "{:?} {:?}", a, b);
+                assert_eq!(b.intersect_dg(a), expected, "{:?} {:?}", b, a);
+                assert_eq!(a.intersect(b), expected, "{:?} {:?}", a, b);
+            }
+        }
+    }
+
+    fn base() -> Triangle {
+        triangle![[0, 0, 0], [1, 0, 0], [0, 1, 0]]
+    }
+
+    #[test]
+    fn one_side_of_plane() {
+        assert_dg(&base(), &triangle![[0, 0, 1], [1, 0, 1], [0, 1, 2]], false);
+        assert_dg(&base(), &triangle![[0, 0, 1], [1, 0, 1], [0, 1, 1]], false);
+    }
+
+    #[test]
+    fn line_intervals() {
+        // Each cuts the other's plane, but the cuts on the common line miss
+        assert_dg(&base(), &triangle![[2, 2, -1], [2, 2, 1], [3, 1, 0]], false);
+        // They overlap
+        assert_dg(
+            &base(),
+            &triangle![[0.2, 0.2, -1], [0.2, 0.2, 1], [0.6, 0.1, 0]],
+            true,
+        );
+        // They meet end to end at (1, 0, 0)
+        assert_dg(&base(), &triangle![[1, 0, -1], [1, 0, 1], [2, 0, 0]], true);
+    }
+
+    #[test]
+    fn vertex_in_plane() {
+        assert_dg(
+            &base(),
+            &triangle![[0.2, 0.2, 0], [0.2, 0.2, 1], [0.5, 0.5, 1]],
+            true,
+        );
+        assert_dg(
+            &base(),
+            &triangle![[0.5, 0.5, 0], [0.5, 0.5, 1], [1, 1, 1]],
+            true,
+        );
+        assert_dg(&base(), &triangle![[1, 1, 0], [1, 1, 1], [2, 2, 1]], false);
+        assert_dg(
+            &base(),
+            &triangle![[0, 0, 0], [-1, 0, -1], [0, -1, -1]],
+            true,
+        );
+    }
+
+    #[test]
+    fn edge_in_plane() {
+        assert_dg(
+            &base(),
+            &triangle![[-1, 0.3, 0], [2, 0.3, 0], [0, 0.3, 1]],
+            true,
+        );
+        assert_dg(&base(), &triangle![[0, 0, 0], [1, 0, 0], [0, -1, 1]], true);
+        assert_dg(&base(), &triangle![[1, 1, 0], [2, 0, 0], [1, 1, 1]], false);
+    }
+
+    #[test]
+    fn coplanar() {
+        // Edges crossing
+        assert_dg(
+            &base(),
+            &triangle![[0.5, -0.5, 0], [0.5, 1, 0], [-0.5, 0.5, 0]],
+            true,
+        );
+        // One inside the other
+        assert_dg(
+            &base(),
+            &triangle![[0.1, 0.1, 0], [0.3, 0.1, 0], [0.1, 0.3, 0]],
+            true,
+        );
+        // Shared vertex, shared edge, identical
+        assert_dg(&base(), &triangle![[1, 0, 0], [2, 0, 0], [2, 1, 0]], true);
+        assert_dg(&base(), &triangle![[1, 0, 0], [0, 1, 0], [1, 1, 0]], true);
+        assert_dg(&base(), &base(), true);
+        // Vertex on an edge
+        assert_dg(
+            &base(),
+            &triangle![[0.5, 0.5, 0], [1, 1, 0], [2, 0.5, 0]],
+            true,
+        );
+        // Overlapping bounding boxes, but apart
+        assert_dg(
+            &base(),
+            &triangle![[1, 1, 0], [0.6, 0.6, 0], [1, 0.6, 0]],
+            false,
+        );
+    }
+}
@@ -0,0 +1,168 @@
+/*
+ * Closest point queries, after Ericson's Real-Time Collision Detection,
+ * chapter 5.
+ */
+
+use crate::Vertex;
+
+fn clamp01(t: f64) -> f64 {
+    t.clamp(0.0, 1.0)
+}
+
+pub(crate) fn closest_on_segment(p: &Vertex, a: &Vertex, b: &Vertex) -> Vertex {
+    let ab = b.subtract(a);
+    let len_sq = ab.dot_product(&ab);
+    if len_sq == 0.0 {
+        return *a;
+    }
+
+    a.add(&ab.scale(clamp01(p.subtract(a).dot_product(&ab) / len_sq)))
+}
+
+/* Closest points (c1, c2) of the segments p1 q1 and p2 q2 */
+pub(crate) fn closest_between_segments(
+    p1: &Vertex,
+    q1: &Vertex,
+    p2: &Vertex,
+    q2: &Vertex,
+) -> (Vertex, Vertex) {
+    let d1 = q1.subtract(p1);
+    let d2 = q2.subtract(p2);
+    let r = p1.subtract(p2);
+    let a = d1.dot_product(&d1);
+    let e = d2.dot_product(&d2);
+    let f = d2.dot_product(&r);
+
+    let (s, t) = if a == 0.0 && e == 0.0 {
+        (0.0, 0.0)
+    } else if a == 0.0 {
+        (0.0, clamp01(f / e))
+    } else {
+        let c = d1.dot_product(&r);
+        if e == 0.0 {
+            (clamp01(-c / a), 0.0)
+        } else {
+            let b = d1.dot_product(&d2);
+            let denom = a * e - b * b;
+            let s = if denom != 0.0 {
+                clamp01((b * f - c * e) / denom)
+            } else {
+                0.0
+            };
+
+            let t = (b * s + f) / e;
+            if t < 0.0 {
+                (clamp01(-c / a), 0.0)
+            } else if t > 1.0 {
+                (clamp01((b - c) / a), 1.0)
+            } else {
+                (s, t)
+            }
+        }
+    };
+
+    (p1.add(&d1.scale(s)), p2.add(&d2.scale(t)))
+}
+
+/*
+ * Closest point to p on the non-degenerate triangle a b c, found by which
+ * Voronoi region of the triangle p falls in.
+ */
+pub(crate) fn closest_on_triangle(p: &Vertex, a: &Vertex, b: &Vertex, c: &Vertex) -> Vertex {
+    let ab = b.subtract(a);
+    let ac = c.subtract(a);
+
+    let ap = p.subtract(a);
+    let d1 = ab.dot_product(&ap);
+    let d2 = ac.dot_product(&ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return *a;
+    }
+
+    let bp = p.subtract(b);
+    let d3 = ab.dot_product(&bp);
+    let d4 = ac.dot_product(&bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return *b;
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a.add(&ab.scale(d1 / (d1 - d3)));
+    }
+
+    let cp = p.subtract(c);
+    let d5 = ab.dot_product(&cp);
+    let d6 = ac.dot_product(&cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return *c;
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a.add(&ac.scale(d2 / (d2 - d6)));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        return b.add(&c.subtract(b).scale((d4 - d3) / ((d4 - d3) + (d5 - d6))));
+    }
+
+    let denom = 1.0 / (va + vb + vc);
+    a.add(&ab.scale(vb * denom)).add(&ac.scale(vc * denom))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(x: f64, y: f64, z: f64) -> Vertex {
+        Vertex::new(x, y, z)
+    }
+
+    #[test]
+    fn closest_on_triangle_regions() {
+        let (a, b, c) = (v(0.0, 0.0, 0.0), v(2.0, 0.0, 0.0), v(0.0, 2.0, 0.0));
+        let closest = |p: Vertex| closest_on_triangle(&p, &a, &b, &c);
+
+        // Over the face, beyond each vertex and beyond each edge
+        assert_eq!(closest(v(0.5, 0.5, 3.0)), v(0.5, 0.5, 0.0));
+        assert_eq!(closest(v(-1.0, -1.0, 1.0)), a);
+        assert_eq!(closest(v(3.0, -1.0, 0.0)), b);
+        assert_eq!(closest(v(-1.0, 3.0, -2.0)), c);
+        assert_eq!(closest(v(1.0, -1.0, 1.0)), v(1.0, 0.0, 0.0));
+        assert_eq!(closest(v(-1.0, 1.5, 0.0)), v(0.0, 1.5, 0.0));
+        assert!(closest(v(2.0, 2.0, 1.0)).abs_diff_eq(&v(1.0, 1.0, 0.0), 1e-12));
+    }
+
+    #[test]
+    fn closest_between_segments_cases() {
+        // Crossing at right angles, 1 apart
+        let (c1, c2) = closest_between_segments(
+            &v(-1.0, 0.0, 0.0),
+            &v(1.0, 0.0, 0.0),
+            &v(0.0, -1.0, 1.0),
+            &v(0.0, 1.0, 1.0),
+        );
+        assert_eq!((c1, c2), (v(0.0, 0.0, 0.0), v(0.0, 0.0, 1.0)));
+
+        // Collinear and apart, and a point against a segment
+        let (c1, c2) = closest_between_segments(
+            &v(0.0, 0.0, 0.0),
+            &v(1.0, 0.0, 0.0),
+            &v(3.0, 0.0, 0.0),
+            &v(4.0, 0.0, 0.0),
+        );
+        assert_eq!(c2.subtract(&c1).length(), 2.0);
+        let p = v(0.5, 2.0, 0.0);
+        assert_eq!(
+            closest_between_segments(&p, &p, &v(0.0, 0.0, 0.0), &v(1.0, 0.0, 0.0)),
+            (p, v(0.5, 0.0, 0.0))
+        );
+
+        assert_eq!(
+            closest_on_segment(&v(5.0, 1.0, 0.0), &v(0.0, 0.0, 0.0), &v(1.0, 0.0, 0.0)),
+            v(1.0, 0.0, 0.0)
+        );
+    }
+}
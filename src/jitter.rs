@@ -0,0 +1,133 @@
+/*
+ * Majority vote over randomly perturbed copies of a borderline pair. The
+ * perturbations come from a xorshift generator seeded by the coordinates,
+ * so the same pair always gets the same answer.
+ */
+
+use std::cmp::Ordering;
+
+use crate::{Triangle, Vertex};
+
+/* Marsaglia's xorshift64, state must be non-zero */
+pub(crate) struct XorShift(pub(crate) u64);
+
+impl XorShift {
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /* Uniform in [-1, 1) */
+    pub(crate) fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+    }
+}
+
+/* FNV-1a over the coordinate bits of both triangles */
+fn seed(a: &Triangle, b: &Triangle) -> u64 {
+    let hash = a
+        .vertices()
+        .iter()
+        .chain(b.vertices())
+        .flat_map(|v| v.to_array())
+        .fold(0xcbf2_9ce4_8422_2325, |h: u64, c| {
+            (h ^ c.to_bits()).wrapping_mul(0x0100_0000_01b3)
+        });
+
+    hash.max(1)
+}
+
+/* Is some vertex of `t` within `eps` of the plane of `plane`? */
+fn near_plane(plane: &Triangle, t: &Triangle, eps: f64) -> bool {
+    let (n, d) = plane.plane_equation();
+    t.vertices()
+        .iter()
+        .any(|p| (n.dot_product(p) - d).abs() <= eps)
+}
+
+fn perturbed(t: &Triangle, rng: &mut XorShift, eps: f64) -> Triangle {
+    let [u, v, w] = t.vertices().map(|p| {
+        Vertex::new(
+            p.x + eps * rng.unit(),
+            p.y + eps * rng.unit(),
+            p.z + eps * rng.unit(),
+        )
+    });
+
+    Triangle::new(u, v, w)
+}
+
+impl Triangle {
+    /// `intersect`, but when some vertex of either triangle lies within
+    /// `eps` of the other's plane, the answer is a majority vote over
+    /// `samples` copies of the pair with every coordinate moved by up to
+    /// `eps`. Ties, and `samples` of 0, fall back to the plain answer. The
+    /// perturbations are seeded by the coordinates, so results are
+    /// repeatable.
+    ///
+    /// This is a heuristic for razor-edge inputs, not a guarantee: it can
+    /// still disagree with exact arithmetic.
+    pub fn intersect_robust_jitter(&self, other: &Triangle, samples: usize, eps: f64) -> bool {
+        let plain = self.intersect(other);
+
+        if !near_plane(self, other, eps) && !near_plane(other, self, eps) {
+            return plain;
+        }
+
+        let mut rng = XorShift(seed(self, other));
+        let hits = (0..samples)
+            .filter(|_| perturbed(self, &mut rng, eps).intersect(&perturbed(other, &mut rng, eps)))
+            .count();
+
+        match (2 * hits).cmp(&samples) {
+            Ordering::Greater => true,
+            Ordering::Less => false,
+            Ordering::Equal => plain,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::triangle;
+
+    #[test]
+    fn razor_edge_vote_is_repeatable() {
+        let a = triangle![[0, 0, 0], [1, 0, 0], [0, 1, 0]];
+        // One vertex 1e-13 above a's interior, the rest well above
+        let b = triangle![[0.3, 0.3, 1e-13], [0.3, 0.3, 1], [0.6, 0.3, 1]];
+        assert!(near_plane(&a, &b, 1e-9));
+
+        let vote = a.intersect_robust_jitter(&b, 101, 1e-9);
+        for _ in 0..10 {
+            assert_eq!(a.intersect_robust_jitter(&b, 101, 1e-9), vote);
+        }
+        assert_eq!(seed(&a, &b), seed(&a.clone(), &b.clone()));
+
+        // Perturbations far smaller than the gap can't change the answer
+        assert_eq!(a.intersect_robust_jitter(&b, 101, 1e-15), a.intersect(&b));
+        assert_eq!(a.intersect_robust_jitter(&b, 0, 1e-9), a.intersect(&b));
+    }
+
+    #[test]
+    fn clear_pairs_skip_the_vote() {
+        let a = triangle![[0, 0, 0], [1, 0, 0], [0, 1, 0]];
+        let crossing = triangle![[0.2, 0.2, -1], [0.2, 0.2, 1], [0.6, 0.1, 0.5]];
+        let apart = triangle![[5, 5, 5], [6, 5, 5], [5, 6, 6]];
+
+        assert!(a.intersect_robust_jitter(&crossing, 11, 1e-9));
+        assert!(!a.intersect_robust_jitter(&apart, 11, 1e-9));
+    }
+
+    #[test]
+    fn unit_stays_in_range() {
+        let mut rng = XorShift(1);
+        for _ in 0..10_000 {
+            let u = rng.unit();
+            assert!((-1.0..1.0).contains(&u));
+        }
+    }
+}
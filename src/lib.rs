@@ -0,0 +1,2211 @@
+use std::f64::consts::FRAC_PI_2;
+use std::ops::Index;
+
+mod batch;
+mod builder;
+mod bvh;
+mod devillers_guigue;
+mod distance;
+mod jitter;
+mod mesh;
+mod planar;
+mod ply;
+mod polygon;
+mod scene;
+mod stl;
+mod transform;
+
+pub use batch::{
+    canonicalize_pairs, detect_fold, find_intersecting_pairs, find_intersecting_pairs_chunked,
+    find_intersecting_pairs_with, find_intersecting_tags, write_pairs_csv, BatchResult, Degenerate,
+    TaggedTriangle,
+};
+pub use builder::{TriangleBuilder, WindingWarning};
+pub use mesh::{mesh_mesh_intersection_segments, Mesh, Triangles};
+pub use ply::read_ply;
+pub use polygon::{ConvexPolygon, Quad};
+pub use scene::Scene;
+pub use stl::{read_stl, stream_self_intersections, write_stl};
+pub use transform::{Quat, Transform};
+
+/// `Triangle::new` from three `[x, y, z]` vertices, whose coordinates may be
+/// integer or float expressions: `triangle![[0, 0, 0], [1, 0, 0], [0, 0.5, 0]]`.
+#[macro_export]
+macro_rules! triangle {
+    (
+        [$ux:expr, $uy:expr, $uz:expr],
+        [$vx:expr, $vy:expr, $vz:expr],
+        [$wx:expr, $wy:expr, $wz:expr] $(,)?
+    ) => {
+        $crate::Triangle::new(
+            $crate::Vertex::new($ux as f64, $uy as f64, $uz as f64),
+            $crate::Vertex::new($vx as f64, $vy as f64, $vz as f64),
+            $crate::Vertex::new($wx as f64, $wy as f64, $wz as f64),
+        )
+    };
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Vertex {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Vertex {
+    pub fn new(x: f64, y: f64, z: f64) -> Vertex {
+        Vertex { x, y, z }
+    }
+
+    pub fn add(&self, v: &Vertex) -> Vertex {
+        Vertex {
+            x: self.x + v.x,
+            y: self.y + v.y,
+            z: self.z + v.z,
+        }
+    }
+
+    pub fn subtract(&self, v: &Vertex) -> Vertex {
+        Vertex {
+            x: self.x - v.x,
+            y: self.y - v.y,
+            z: self.z - v.z,
+        }
+    }
+
+    pub fn cross_product(&self, v: &Vertex) -> Vertex {
+        // Yeah, I am using a Vertex as a 3-by-1 matrix...
+        Vertex {
+            x: (self.y * v.z) - (self.z * v.y),
+            y: -((self.x * v.z) - (self.z * v.x)),
+            z: (self.x * v.y) - (self.y * v.x),
+        }
+    }
+
+    pub fn dot_product(&self, v: &Vertex) -> f64 {
+        (self.x * v.x) + (self.y * v.y) + (self.z * v.z)
+    }
+
+    pub fn component(&self, axis: Axis) -> f64 {
+        match axis {
+            Axis::X => self.x,
+            Axis::Y => self.y,
+            Axis::Z => self.z,
+        }
+    }
+
+    pub fn scale(&self, s: f64) -> Vertex {
+        Vertex {
+            x: self.x * s,
+            y: self.y * s,
+            z: self.z * s,
+        }
+    }
+
+    pub fn length(&self) -> f64 {
+        self.dot_product(self).sqrt()
+    }
+
+    pub fn midpoint(&self, v: &Vertex) -> Vertex {
+        self.add(v).scale(0.5)
+    }
+
+    /// Vector projection of `self` onto `onto`. Not finite when `onto` is
+    /// the zero vector.
+    pub fn project_onto(&self, onto: &Vertex) -> Vertex {
+        onto.scale(self.dot_product(onto) / onto.dot_product(onto))
+    }
+
+    /// Mirror `self` across the plane through the origin with the given
+    /// normal, which need not be unit length.
+    pub fn reflect(&self, normal: &Vertex) -> Vertex {
+        self.subtract(&self.project_onto(normal).scale(2.0))
+    }
+
+    /// Each coordinate rounded to `decimals` places, with -0.0 normalized to
+    /// 0.0. Meant for display only; rounding before computing loses accuracy.
+    pub fn round_to(&self, decimals: i32) -> Vertex {
+        let m = 10f64.powi(decimals);
+        let round = |c: f64| (c * m).round() / m + 0.0;
+
+        Vertex::new(round(self.x), round(self.y), round(self.z))
+    }
+
+    /// Each coordinate differs by at most `epsilon`. Same semantics as the
+    /// `approx` crate's `AbsDiffEq`.
+    pub fn abs_diff_eq(&self, v: &Vertex, epsilon: f64) -> bool {
+        (self.x - v.x).abs() <= epsilon
+            && (self.y - v.y).abs() <= epsilon
+            && (self.z - v.z).abs() <= epsilon
+    }
+
+    /// Each coordinate is within `epsilon`, or within `max_relative` of the
+    /// larger magnitude. Same semantics as the `approx` crate's `RelativeEq`.
+    pub fn relative_eq(&self, v: &Vertex, epsilon: f64, max_relative: f64) -> bool {
+        let eq = |a: f64, b: f64| {
+            let diff = (a - b).abs();
+            diff <= epsilon || diff <= a.abs().max(b.abs()) * max_relative
+        };
+
+        eq(self.x, v.x) && eq(self.y, v.y) && eq(self.z, v.z)
+    }
+
+    /// Coordinates as `[x, y, z]`.
+    pub fn to_array(&self) -> [f64; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    /// Vertex from `[x, y, z]`.
+    pub fn from_array([x, y, z]: [f64; 3]) -> Vertex {
+        Vertex::new(x, y, z)
+    }
+}
+
+/// Coordinates by position: 0 is x, 1 is y and 2 is z. Panics otherwise.
+impl Index<usize> for Vertex {
+    type Output = f64;
+
+    fn index(&self, i: usize) -> &f64 {
+        match i {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("Vertex index out of range: {}", i),
+        }
+    }
+}
+
+impl From<[f64; 3]> for Vertex {
+    fn from(coords: [f64; 3]) -> Vertex {
+        Vertex::from_array(coords)
+    }
+}
+
+impl From<Vertex> for [f64; 3] {
+    fn from(v: Vertex) -> [f64; 3] {
+        v.to_array()
+    }
+}
+
+/// Axis-aligned bounding box, closed on every side.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    pub min: Vertex,
+    pub max: Vertex,
+}
+
+impl Aabb {
+    pub fn overlaps(&self, other: &Aabb) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+            && self.min.z <= other.max.z
+            && other.min.z <= self.max.z
+    }
+}
+
+/// Oriented bounding box: `center` plus `half_extents[i]` along each of the
+/// orthonormal `axes[i]` in both directions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Obb {
+    pub center: Vertex,
+    pub axes: [Vertex; 3],
+    pub half_extents: [f64; 3],
+}
+
+/// A coordinate axis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+/// Apparent vertex order of a triangle seen from a viewing direction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Winding {
+    Clockwise,
+    CounterClockwise,
+    EdgeOn,
+}
+
+/// How two intersecting triangles meet, from `Triangle::intersection_detail`.
+#[derive(Clone, Debug)]
+pub enum Intersection {
+    /// The triangles pass through each other's interiors along this segment,
+    /// which has positive length.
+    Crossing(Edge),
+    /// The triangles are coplanar and overlap in this convex polygon of
+    /// positive area, its corners in order.
+    Coplanar(Vec<Vertex>),
+    /// The triangles meet only on the boundary of at least one of them, e.g.
+    /// at a vertex or along an edge. Holds the points of contact; contact
+    /// along a segment is given by its endpoints.
+    Touching(Vec<Vertex>),
+}
+
+#[derive(Clone, Debug)]
+pub struct Edge {
+    pub vertices: [Vertex; 2],
+}
+
+impl Edge {
+    pub fn new(u: Vertex, v: Vertex) -> Edge {
+        Edge { vertices: [u, v] }
+    }
+
+    /// The second vertex minus the first.
+    pub fn direction(&self) -> Vertex {
+        self.vertices[1].subtract(&self.vertices[0])
+    }
+}
+
+/* A triangle's plane, prepared for testing many edges against it */
+struct PlaneData {
+    /* Unnormalized normal, as from Triangle::normal */
+    n: Vertex,
+    /* Square of the largest n o (p - a) for p to count as in the plane */
+    tol_sq: f64,
+    axis: Axis,
+    projected: [planar::Point2; 3],
+}
+
+#[derive(Clone, Debug)]
+pub struct Triangle {
+    vertices: [Vertex; 3],
+    edges: [Edge; 3],
+}
+
+/*
+ * V = (1/6) * det(a - d, b - d, c - d)
+ * V = (1/6) * (([a - d] X [b - d]) o [c - d])
+ *
+ * Link: https://en.wikipedia.org/wiki/Tetrahedron#Volume
+ */
+fn tetrahedran_signed_volume(a: &Vertex, b: &Vertex, c: &Vertex, d: &Vertex) -> f64 {
+    (a.subtract(d)
+        .cross_product(&b.subtract(d))
+        .dot_product(&c.subtract(d)))
+        / 6.0
+}
+
+/// Relative tolerance used throughout the crate: points within this
+/// fraction of a triangle's longest edge from its plane are treated as lying
+/// in the plane, and near-parallel or near-degenerate cases are judged
+/// against it the same way. `intersect` uses it.
+pub const DEFAULT_EPS: f64 = 1e-9;
+
+/// `DEFAULT_EPS` scaled to the longest edge among `triangles`, as an
+/// absolute tolerance for the functions taking one, such as `are_coplanar`
+/// and `contact_points`. 0 when `triangles` is empty.
+pub fn default_eps_for(triangles: &[&Triangle]) -> f64 {
+    DEFAULT_EPS
+        * triangles
+            .iter()
+            .map(|t| t.longest_edge())
+            .fold(0.0, f64::max)
+}
+
+/*
+ * One Sutherland-Hodgman pass: keep the part of a convex polygon on one side
+ * of the plane where the given coordinate equals bound.
+ *
+ * Link: https://en.wikipedia.org/wiki/Sutherland%E2%80%93Hodgman_algorithm
+ */
+fn clip_polygon(poly: &[Vertex], axis: Axis, bound: f64, keep_below: bool) -> Vec<Vertex> {
+    let inside = |v: &Vertex| {
+        if keep_below {
+            v.component(axis) <= bound
+        } else {
+            v.component(axis) >= bound
+        }
+    };
+
+    let mut clipped = Vec::new();
+    for (i, p) in poly.iter().enumerate() {
+        let q = &poly[(i + 1) % poly.len()];
+
+        if inside(p) {
+            clipped.push(*p);
+        }
+
+        if inside(p) != inside(q) {
+            let t = (bound - p.component(axis)) / (q.component(axis) - p.component(axis));
+            clipped.push(p.add(&q.subtract(p).scale(t)));
+        }
+    }
+
+    clipped
+}
+
+/* Triangulate a convex polygon as a fan around its first vertex */
+fn fan(poly: &[Vertex]) -> Vec<Triangle> {
+    (1..poly.len().saturating_sub(1))
+        .map(|i| Triangle::new(poly[0], poly[i], poly[i + 1]))
+        .collect()
+}
+
+impl Triangle {
+    pub fn new(u: Vertex, v: Vertex, w: Vertex) -> Triangle {
+        Triangle {
+            vertices: [u, v, w],
+            edges: [Edge::new(u, v), Edge::new(v, w), Edge::new(w, u)],
+        }
+    }
+
+    pub fn vertices(&self) -> &[Vertex; 3] {
+        &self.vertices
+    }
+
+    /// The edges (u, v), (v, w) and (w, u).
+    pub fn edges(&self) -> &[Edge; 3] {
+        &self.edges
+    }
+
+    /* Link: https://stackoverflow.com/a/42752998 */
+    fn edge_intersect(&self, e: &Edge) -> bool {
+        self.edge_intersect_plane(&self.plane_data(), e)
+    }
+
+    pub fn aabb(&self) -> Aabb {
+        let [u, v, w] = self.vertices;
+
+        Aabb {
+            min: Vertex::new(
+                u.x.min(v.x).min(w.x),
+                u.y.min(v.y).min(w.y),
+                u.z.min(v.z).min(w.z),
+            ),
+            max: Vertex::new(
+                u.x.max(v.x).max(w.x),
+                u.y.max(v.y).max(w.y),
+                u.z.max(v.z).max(w.z),
+            ),
+        }
+    }
+
+    /// The smallest box aligned to the triangle's plane. Its first two axes
+    /// lie in the plane, the first along one of the edges, and the third is
+    /// the unit normal, along which the extent is zero. The values are not
+    /// finite for a degenerate triangle.
+    pub fn obb(&self) -> Obb {
+        let (n, _) = self.plane_equation();
+        let o = self.vertices[0];
+
+        let range = |axis: &Vertex| {
+            self.vertices
+                .iter()
+                .map(|p| p.subtract(&o).dot_product(axis))
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), d| {
+                    (lo.min(d), hi.max(d))
+                })
+        };
+
+        /* The minimal rectangle around a convex polygon has a side on one of its edges */
+        self.edges
+            .iter()
+            .map(|e| {
+                let u = e.direction();
+                let u = u.scale(1.0 / u.length());
+                let v = n.cross_product(&u);
+
+                let (u_lo, u_hi) = range(&u);
+                let (v_lo, v_hi) = range(&v);
+
+                Obb {
+                    center: o
+                        .add(&u.scale((u_lo + u_hi) / 2.0))
+                        .add(&v.scale((v_lo + v_hi) / 2.0)),
+                    axes: [u, v, n],
+                    half_extents: [(u_hi - u_lo) / 2.0, (v_hi - v_lo) / 2.0, 0.0],
+                }
+            })
+            .min_by(|a, b| {
+                let area = |obb: &Obb| obb.half_extents[0] * obb.half_extents[1];
+                area(a).total_cmp(&area(b))
+            })
+            .unwrap()
+    }
+
+    pub fn centroid(&self) -> Vertex {
+        self.vertices[0]
+            .add(&self.vertices[1])
+            .add(&self.vertices[2])
+            .scale(1.0 / 3.0)
+    }
+
+    /// Center of the circle through all three vertices, `None` for a
+    /// degenerate triangle.
+    ///
+    /// Link: https://en.wikipedia.org/wiki/Circumcircle#Higher_dimensions
+    pub fn circumcenter(&self) -> Option<Vertex> {
+        if self.is_degenerate() {
+            return None;
+        }
+
+        let [u, v, w] = &self.vertices;
+        let a = u.subtract(w);
+        let b = v.subtract(w);
+        let axb = a.cross_product(&b);
+
+        let offset = b
+            .scale(a.dot_product(&a))
+            .subtract(&a.scale(b.dot_product(&b)))
+            .cross_product(&axb)
+            .scale(1.0 / (2.0 * axb.dot_product(&axb)));
+
+        Some(w.add(&offset))
+    }
+
+    /// Center of the inscribed circle: the vertices weighted by the lengths
+    /// of their opposite sides.
+    pub fn incenter(&self) -> Vertex {
+        let [u, v, w] = &self.vertices;
+        let (lu, lv, lw) = (
+            v.subtract(w).length(),
+            w.subtract(u).length(),
+            u.subtract(v).length(),
+        );
+
+        u.scale(lu)
+            .add(&v.scale(lv))
+            .add(&w.scale(lw))
+            .scale(1.0 / (lu + lv + lw))
+    }
+
+    /// Distance between the centroids, a cheap proximity key for sorting.
+    pub fn centroid_distance(&self, other: &Triangle) -> f64 {
+        self.centroid().subtract(&other.centroid()).length()
+    }
+
+    /// This triangle scaled by `factor` about its own centroid.
+    pub fn scale_about_centroid(&self, factor: f64) -> Triangle {
+        let c = self.centroid();
+        let [u, v, w] = self.vertices.map(|v| c.add(&v.subtract(&c).scale(factor)));
+
+        Triangle::new(u, v, w)
+    }
+
+    /// Barycentric weights (u, v, w) of `p`, projected onto the triangle's
+    /// plane, with respect to the vertices in order. Not finite for a
+    /// degenerate triangle.
+    ///
+    /// Link: https://gamedev.stackexchange.com/a/23745
+    pub fn barycentric(&self, p: &Vertex) -> (f64, f64, f64) {
+        let v0 = self.vertices[1].subtract(&self.vertices[0]);
+        let v1 = self.vertices[2].subtract(&self.vertices[0]);
+        let v2 = p.subtract(&self.vertices[0]);
+
+        let d00 = v0.dot_product(&v0);
+        let d01 = v0.dot_product(&v1);
+        let d11 = v1.dot_product(&v1);
+        let d20 = v2.dot_product(&v0);
+        let d21 = v2.dot_product(&v1);
+        let denom = d00 * d11 - d01 * d01;
+
+        let v = (d11 * d20 - d01 * d21) / denom;
+        let w = (d00 * d21 - d01 * d20) / denom;
+
+        (1.0 - v - w, v, w)
+    }
+
+    /// The point with barycentric weights (u, v, w), the inverse of
+    /// `barycentric`. Weights summing to 1 give a point in the plane.
+    pub fn point_from_barycentric(&self, u: f64, v: f64, w: f64) -> Vertex {
+        self.vertices[0]
+            .scale(u)
+            .add(&self.vertices[1].scale(v))
+            .add(&self.vertices[2].scale(w))
+    }
+
+    /// Whether (x, y) lies inside or on the boundary of the triangle's
+    /// projection onto the XY plane, ignoring z entirely.
+    pub fn contains_point_2d(&self, x: f64, y: f64) -> bool {
+        let t = self.vertices.map(|v| planar::project(&v, Axis::Z));
+        planar::point_in_triangle((x, y), &t)
+    }
+
+    /// Interior angle in radians at each vertex, in vertex order.
+    pub fn interior_angles(&self) -> [f64; 3] {
+        [0, 1, 2].map(|i| {
+            let p = &self.vertices[i];
+            let u = self.vertices[(i + 1) % 3].subtract(p);
+            let v = self.vertices[(i + 2) % 3].subtract(p);
+
+            u.cross_product(&v).length().atan2(u.dot_product(&v))
+        })
+    }
+
+    fn largest_angle(&self) -> f64 {
+        self.interior_angles().iter().copied().fold(0.0, f64::max)
+    }
+
+    /// Largest angle within `eps` radians of a right angle. For any `eps`,
+    /// exactly one of `is_acute`, `is_right` and `is_obtuse` holds.
+    pub fn is_right(&self, eps: f64) -> bool {
+        (self.largest_angle() - FRAC_PI_2).abs() <= eps
+    }
+
+    /// Largest angle more than `eps` radians over a right angle.
+    pub fn is_obtuse(&self, eps: f64) -> bool {
+        self.largest_angle() > FRAC_PI_2 + eps
+    }
+
+    /// Every angle more than `eps` radians under a right angle.
+    pub fn is_acute(&self, eps: f64) -> bool {
+        self.largest_angle() < FRAC_PI_2 - eps
+    }
+
+    /// Whether the triangle has (near) zero area: its vertices coincide or
+    /// are collinear to within a tiny fraction of its size.
+    pub fn is_degenerate(&self) -> bool {
+        self.normal().length() <= DEFAULT_EPS * self.longest_edge_sq()
+    }
+
+    /// This triangle with every coordinate rounded to the nearest multiple
+    /// of `grid`, to undo noise like 0.9999999 that should have been 1.0.
+    pub fn snap_coords(&self, grid: f64) -> Triangle {
+        let snap = |c: f64| (c / grid).round() * grid;
+
+        Triangle::from(
+            self.vertices
+                .map(|v| Vertex::new(snap(v.x), snap(v.y), snap(v.z))),
+        )
+    }
+
+    /// Midpoints of the edges (u, v), (v, w) and (w, u).
+    pub fn edge_midpoints(&self) -> [Vertex; 3] {
+        [0, 1, 2].map(|i| self.edges[i].vertices[0].midpoint(&self.edges[i].vertices[1]))
+    }
+
+    fn longest_edge(&self) -> f64 {
+        self.longest_edge_sq().sqrt()
+    }
+
+    fn longest_edge_sq(&self) -> f64 {
+        self.edges
+            .iter()
+            .map(|e| {
+                let d = e.vertices[1].subtract(&e.vertices[0]);
+                d.dot_product(&d)
+            })
+            .fold(0.0, f64::max)
+    }
+
+    /// Unnormalized normal, (b - a) X (c - a). This is all the triangle side
+    /// of the signed volume test needs, so compute it once for many edges.
+    pub fn normal(&self) -> Vertex {
+        self.vertices[1]
+            .subtract(&self.vertices[0])
+            .cross_product(&self.vertices[2].subtract(&self.vertices[0]))
+    }
+
+    /* Everything edge_intersect_plane needs from the triangle, from one normal */
+    fn plane_data(&self) -> PlaneData {
+        let n = self.normal();
+        let axis = planar::dominant_axis(&n);
+
+        PlaneData {
+            n,
+            tol_sq: DEFAULT_EPS * DEFAULT_EPS * n.dot_product(&n) * self.longest_edge_sq(),
+            axis,
+            projected: self.vertices.map(|v| planar::project(&v, axis)),
+        }
+    }
+
+    /*
+     * Does the edge e meet this triangle, whose plane is `plane`?
+     *
+     * Boundary convention for the signed volumes: a volume of exactly zero
+     * means the point lies on the plane (or the line on the edge), and such
+     * contacts count as intersecting. They are decided explicitly rather than
+     * by giving zero a sign, which would make the answer depend on vertex
+     * order: a.intersect(b) is the same for every ordering of either
+     * triangle's vertices and for b.intersect(a).
+     */
+    fn edge_intersect_plane(&self, plane: &PlaneData, e: &Edge) -> bool {
+        // Use each point in the provided edge. The signed volume of
+        // (a, b, c, p) is -(n o [p - a]) / 6, so these have its sign flipped.
+        let sv_e = e
+            .vertices
+            .map(|v| plane.n.dot_product(&v.subtract(&self.vertices[0])));
+
+        // An edge lying in the plane makes both volumes vanish and the test
+        // below meaningless, so decide it in 2D instead
+        let tol_sq = plane.tol_sq;
+        if tol_sq > 0.0 && sv_e.iter().all(|sv| sv * sv <= tol_sq) {
+            let p = e.vertices.map(|v| planar::project(&v, plane.axis));
+
+            return planar::segment_intersects_triangle(p[0], p[1], &plane.projected);
+        }
+
+        // A degenerate triangle spans no plane to cross. The other triangle's
+        // edges tested against it cover the contact.
+        if tol_sq == 0.0 {
+            return false;
+        }
+
+        // Both endpoints strictly on one side. An endpoint exactly on the
+        // plane is where the edge meets it.
+        if (sv_e[0] > 0.0 && sv_e[1] > 0.0) || (sv_e[0] < 0.0 && sv_e[1] < 0.0) {
+            return false;
+        }
+
+        // The edge's line passes each of the triangle's edges on the same
+        // side when it goes through the triangle. A zero volume means it
+        // meets that edge, so it only has to agree with the nonzero ones.
+        let sv_t = [0, 1, 2].map(|i| {
+            let edge = &self.edges[i];
+            tetrahedran_signed_volume(
+                &edge.vertices[0],
+                &edge.vertices[1],
+                &e.vertices[0],
+                &e.vertices[1],
+            )
+        });
+
+        sv_t.iter().all(|&sv| sv >= 0.0) || sv_t.iter().all(|&sv| sv <= 0.0)
+    }
+
+    /// The plane n o p = d containing the triangle, with n of unit length so
+    /// that d is the signed distance of the plane from the origin. The values
+    /// are not finite for a degenerate triangle.
+    pub fn plane_equation(&self) -> (Vertex, f64) {
+        let n = self.normal();
+        let n = n.scale(1.0 / n.length());
+
+        (n, n.dot_product(&self.vertices[0]))
+    }
+
+    /// Winding of the vertices as seen by a viewer looking along `view_dir`.
+    /// The vertices appear counterclockwise when the normal faces the viewer.
+    pub fn winding(&self, view_dir: &Vertex) -> Winding {
+        let d = self.normal().dot_product(view_dir);
+
+        if d < 0.0 {
+            Winding::CounterClockwise
+        } else if d > 0.0 {
+            Winding::Clockwise
+        } else {
+            Winding::EdgeOn
+        }
+    }
+
+    /// Project onto the coordinate plane perpendicular to the axis with the
+    /// largest absolute normal component, returning the 2D coordinates and
+    /// the dropped axis. The remaining axes are kept in cyclic order (y, z),
+    /// (z, x) or (x, y), so the 2D winding matches the sign of the dropped
+    /// normal component.
+    pub fn project_2d(&self) -> ([(f64, f64); 3], Axis) {
+        let axis = planar::dominant_axis(&self.normal());
+        (self.vertices.map(|v| planar::project(&v, axis)), axis)
+    }
+
+    /// Signed area of the projection onto the coordinate plane perpendicular
+    /// to `drop_axis`, with the remaining axes in cyclic order as in
+    /// `project_2d`. Positive when the vertices run counterclockwise seen
+    /// from the positive end of `drop_axis`, e.g. counterclockwise in XY for
+    /// `Axis::Z`.
+    pub fn signed_area_2d(&self, drop_axis: Axis) -> f64 {
+        let [a, b, c] = self.vertices.map(|v| planar::project(&v, drop_axis));
+        planar::orient(a, b, c) / 2.0
+    }
+
+    /// The part of the triangle inside the box from `min` to `max`, as a fan
+    /// of triangles. Empty when fully outside or when only a point or a
+    /// segment lies in the box, the triangle itself when fully inside.
+    pub fn clip_to_aabb(&self, min: &Vertex, max: &Vertex) -> Vec<Triangle> {
+        let axes = [Axis::X, Axis::Y, Axis::Z];
+        let in_box = |v: &Vertex| {
+            axes.iter()
+                .all(|&a| v.component(a) >= min.component(a) && v.component(a) <= max.component(a))
+        };
+
+        if self.vertices.iter().all(in_box) {
+            return vec![self.clone()];
+        }
+
+        let mut poly = self.vertices.to_vec();
+        for axis in axes {
+            poly = clip_polygon(&poly, axis, min.component(axis), false);
+            poly = clip_polygon(&poly, axis, max.component(axis), true);
+        }
+
+        // Clipping through a vertex or along an edge repeats points, and
+        // touching the box leaves nothing but repeats
+        poly.dedup();
+        if poly.len() > 1 && poly.first() == poly.last() {
+            poly.pop();
+        }
+
+        let area = fan(&poly)
+            .iter()
+            .fold(Vertex::new(0.0, 0.0, 0.0), |sum, t| sum.add(&t.normal()));
+        if area.length() <= DEFAULT_EPS * self.normal().length() {
+            return Vec::new();
+        }
+
+        fan(&poly)
+    }
+
+    /* Link: https://en.wikipedia.org/wiki/M%C3%B6ller%E2%80%93Trumbore_intersection_algorithm */
+    fn line_parameter(&self, point: &Vertex, dir: &Vertex) -> Option<f64> {
+        let e1 = self.vertices[1].subtract(&self.vertices[0]);
+        let e2 = self.vertices[2].subtract(&self.vertices[0]);
+
+        let p = dir.cross_product(&e2);
+        let det = e1.dot_product(&p);
+        if det.abs() <= DEFAULT_EPS * dir.length() * e1.cross_product(&e2).length() {
+            return None;
+        }
+
+        let s = point.subtract(&self.vertices[0]);
+        let u = s.dot_product(&p) / det;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+
+        let q = s.cross_product(&e1);
+        let v = dir.dot_product(&q) / det;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        Some(e2.dot_product(&q) / det)
+    }
+
+    /// Where the infinite line through `point` along `dir` meets the
+    /// triangle, including behind `point`. `None` when it misses or is
+    /// parallel to the triangle's plane.
+    pub fn line_intersect(&self, point: &Vertex, dir: &Vertex) -> Option<Vertex> {
+        self.line_parameter(point, dir)
+            .map(|t| point.add(&dir.scale(t)))
+    }
+
+    /// Where the segment `e` pierces the triangle, or `None` when it misses.
+    /// A segment lying in (or parallel to) the triangle's plane always gives
+    /// `None`, even when it overlaps the triangle.
+    pub fn segment_hit(&self, e: &Edge) -> Option<Vertex> {
+        let [p, q] = &e.vertices;
+        let dir = q.subtract(p);
+
+        self.line_parameter(p, &dir)
+            .filter(|t| (0.0..=1.0).contains(t))
+            .map(|t| p.add(&dir.scale(t)))
+    }
+
+    /// Indices of the segments in `segs` that cross this triangle.
+    pub fn segments_intersecting(&self, segs: &[Edge]) -> Vec<usize> {
+        let plane = self.plane_data();
+
+        segs.iter()
+            .enumerate()
+            .filter(|(_, e)| self.edge_intersect_plane(&plane, e))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Does the capsule of all points within `radius` of the segment `a` `b`
+    /// touch the triangle?
+    pub fn intersects_capsule(&self, a: &Vertex, b: &Vertex, radius: f64) -> bool {
+        self.segment_distance(&Edge::new(*a, *b)) <= radius
+    }
+
+    /* Distance between the segment e and the closest point of the triangle */
+    fn segment_distance(&self, e: &Edge) -> f64 {
+        let [p, q] = &e.vertices;
+
+        // A degenerate triangle is just its edges, which the segment to edge
+        // distances below already cover
+        let to_face = if self.is_degenerate() {
+            f64::INFINITY
+        } else if self.edge_intersect(e) {
+            return 0.0;
+        } else {
+            let [a, b, c] = &self.vertices;
+            [p, q]
+                .iter()
+                .map(|v| {
+                    distance::closest_on_triangle(v, a, b, c)
+                        .subtract(v)
+                        .length()
+                })
+                .fold(f64::INFINITY, f64::min)
+        };
+
+        self.edges
+            .iter()
+            .map(|edge| {
+                let (c1, c2) =
+                    distance::closest_between_segments(p, q, &edge.vertices[0], &edge.vertices[1]);
+                c1.subtract(&c2).length()
+            })
+            .fold(to_face, f64::min)
+    }
+
+    /// Whether every vertex of `other` is within distance `eps` of this
+    /// triangle's plane. Always false when this triangle is degenerate.
+    pub fn are_coplanar(&self, other: &Triangle, eps: f64) -> bool {
+        self.coplanar_sq(other, eps * eps)
+    }
+
+    /// Largest distance of a vertex of `other` from this triangle's plane,
+    /// for callers choosing their own coplanarity threshold. NaN when this
+    /// triangle is degenerate.
+    pub fn coplanarity_residual(&self, other: &Triangle) -> f64 {
+        let (n, d) = self.plane_equation();
+        if !d.is_finite() {
+            return f64::NAN;
+        }
+
+        other
+            .vertices
+            .iter()
+            .map(|v| (n.dot_product(v) - d).abs())
+            .fold(0.0, f64::max)
+    }
+
+    /* are_coplanar without square roots, since intersect calls it every time */
+    fn coplanar_sq(&self, other: &Triangle, eps_sq: f64) -> bool {
+        let n = self.normal();
+        let n_sq = n.dot_product(&n);
+
+        n_sq > 0.0
+            && other.vertices.iter().all(|v| {
+                let dist = n.dot_product(&v.subtract(&self.vertices[0]));
+                dist * dist <= eps_sq * n_sq
+            })
+    }
+
+    /* Overlap of two triangles known to share a plane, decided in 2D */
+    fn coplanar_intersect(&self, t: &Triangle) -> bool {
+        let (a, axis) = self.project_2d();
+        let b = t.vertices.map(|v| planar::project(&v, axis));
+
+        // An edge of t reaching into self covers every case except self lying
+        // entirely inside t
+        (0..3).any(|i| planar::segment_intersects_triangle(b[i], b[(i + 1) % 3], &a))
+            || planar::point_in_triangle(a[0], &b)
+    }
+
+    /// When the two triangles are coplanar within `eps`, share an edge (with
+    /// vertices matching within `eps`) and together form a convex quad, its
+    /// four corners in this triangle's winding order.
+    pub fn merge_coplanar(&self, other: &Triangle, eps: f64) -> Option<[Vertex; 4]> {
+        if !self.are_coplanar(other, eps) {
+            return None;
+        }
+
+        let shared = |v: &Vertex| other.vertices.iter().position(|o| v.abs_diff_eq(o, eps));
+        let matches = self.vertices.map(|v| shared(&v));
+        if matches.iter().filter(|m| m.is_some()).count() != 2 {
+            return None;
+        }
+
+        // The shared edge is (s0, s1) and c the rest of self, so the quad
+        // replaces s0 -> s1 with s0 -> d -> s1
+        let i = (0..3).find(|&i| matches[i].is_some() && matches[(i + 1) % 3].is_some())?;
+        let (s0, s1, c) = (
+            self.vertices[i],
+            self.vertices[(i + 1) % 3],
+            self.vertices[(i + 2) % 3],
+        );
+        let d = (0..3)
+            .find(|&j| matches[i] != Some(j) && matches[(i + 1) % 3] != Some(j))
+            .map(|j| other.vertices[j])?;
+
+        let quad = [s0, d, s1, c];
+        let (_, axis) = self.project_2d();
+        let p = quad.map(|v| planar::project(&v, axis));
+        let turns = [0, 1, 2, 3].map(|k| planar::orient(p[k], p[(k + 1) % 4], p[(k + 2) % 4]));
+
+        if turns.iter().all(|&t| t > 0.0) || turns.iter().all(|&t| t < 0.0) {
+            Some(quad)
+        } else {
+            None
+        }
+    }
+
+    /// Do the triangles intersect? Touching counts, down to a single shared
+    /// point. Uses `DEFAULT_EPS`; see `intersect_eps`.
+    pub fn intersect(&self, t: &Triangle) -> bool {
+        self.intersect_eps(t, DEFAULT_EPS)
+    }
+
+    /// `intersect`, treating `t` as coplanar with `self` when all its
+    /// vertices are within `eps` times the longest edge of `self` from the
+    /// plane of `self`.
+    pub fn intersect_eps(&self, t: &Triangle, eps: f64) -> bool {
+        if self.coplanar_sq(t, eps * eps * self.longest_edge_sq()) {
+            return self.coplanar_intersect(t);
+        }
+
+        // Check if any edge in t intersects self
+        let plane = self.plane_data();
+        for edge in &t.edges {
+            if self.edge_intersect_plane(&plane, edge) {
+                return true;
+            }
+        }
+
+        // Check if any edge in self intersects t
+        let plane = t.plane_data();
+        for edge in &self.edges {
+            if t.edge_intersect_plane(&plane, edge) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /*
+     * Extremes of where this triangle meets the plane n o p = d, along dir.
+     * Vertices within tol of the plane count as on it, as in intersect.
+     */
+    fn plane_cut(&self, n: &Vertex, d: f64, tol: f64, dir: &Vertex) -> Option<[Vertex; 2]> {
+        let dist = self.vertices.map(|v| n.dot_product(&v) - d);
+        let on = dist.map(|s| s.abs() <= tol);
+        let mut points = Vec::with_capacity(3);
+
+        for i in 0..3 {
+            let j = (i + 1) % 3;
+            let (u, v) = (&self.vertices[i], &self.vertices[j]);
+
+            if on[i] {
+                points.push(*u);
+            } else if !on[j] && dist[i] * dist[j] < 0.0 {
+                let t = dist[i] / (dist[i] - dist[j]);
+                points.push(u.add(&v.subtract(u).scale(t)));
+            }
+        }
+
+        let key = |p: &Vertex| p.dot_product(dir);
+        let min = points
+            .iter()
+            .copied()
+            .min_by(|a, b| key(a).total_cmp(&key(b)))?;
+        let max = points
+            .iter()
+            .copied()
+            .max_by(|a, b| key(a).total_cmp(&key(b)))?;
+
+        Some([min, max])
+    }
+
+    /// The segment along which two non-coplanar triangles cross, or `None`
+    /// when they are disjoint, parallel or coplanar. A single touching point
+    /// gives a zero-length segment.
+    pub fn intersection_segment(&self, other: &Triangle) -> Option<Edge> {
+        if self.is_degenerate()
+            || other.is_degenerate()
+            || self.are_coplanar(other, DEFAULT_EPS * self.longest_edge())
+        {
+            return None;
+        }
+
+        let (n1, d1) = self.plane_equation();
+        let (n2, d2) = other.plane_equation();
+        let dir = n1.cross_product(&n2);
+        if dir.length() <= DEFAULT_EPS {
+            return None;
+        }
+
+        // Both triangles cut the planes' common line in an interval; the
+        // intersection is where the intervals overlap
+        let a = self.plane_cut(&n2, d2, DEFAULT_EPS * other.longest_edge(), &dir)?;
+        let b = other.plane_cut(&n1, d1, DEFAULT_EPS * self.longest_edge(), &dir)?;
+
+        let key = |p: &Vertex| p.dot_product(&dir);
+        let lo = if key(&a[0]) >= key(&b[0]) { a[0] } else { b[0] };
+        let hi = if key(&a[1]) <= key(&b[1]) { a[1] } else { b[1] };
+        if key(&lo) > key(&hi) {
+            return None;
+        }
+
+        Some(Edge::new(lo, hi))
+    }
+
+    /*
+     * Does intersection_segment's `segment` run through the interiors of
+     * both triangles, rather than along a boundary? Only a triangle with
+     * vertices on both sides of the other's plane lets the other through.
+     */
+    fn is_crossing(&self, other: &Triangle, segment: &Edge) -> bool {
+        let straddles = |t: &Triangle, plane: &Triangle| {
+            let (n, d) = plane.plane_equation();
+            let tol = DEFAULT_EPS * plane.longest_edge();
+            let dist = t.vertices.map(|v| n.dot_product(&v) - d);
+
+            dist.iter().any(|&s| s > tol) && dist.iter().any(|&s| s < -tol)
+        };
+
+        let [lo, hi] = &segment.vertices;
+        !lo.abs_diff_eq(hi, DEFAULT_EPS * self.longest_edge())
+            && straddles(self, other)
+            && straddles(other, self)
+    }
+
+    /// Length of `intersection_segment` when the triangles pass through each
+    /// other's interiors. `None` when they only touch, e.g. at a point or
+    /// along a shared edge, as well as when they don't intersect.
+    pub fn intersection_length(&self, other: &Triangle) -> Option<f64> {
+        self.intersection_segment(other)
+            .filter(|e| self.is_crossing(other, e))
+            .map(|e| e.direction().length())
+    }
+
+    /// Points where the triangles touch to within `eps`: coincident
+    /// vertices, vertices lying on the other triangle's edges or face, and
+    /// crossings of non-parallel edges. Each point is reported once.
+    pub fn contact_points(&self, other: &Triangle, eps: f64) -> Vec<Vertex> {
+        let mut candidates = Vec::new();
+
+        for (a, b) in [(self, other), (other, self)] {
+            // A degenerate b is just its edges
+            let on_face = |v: &Vertex| {
+                let [p, q, r] = &b.vertices;
+                !b.is_degenerate()
+                    && distance::closest_on_triangle(v, p, q, r)
+                        .subtract(v)
+                        .length()
+                        <= eps
+            };
+
+            for v in &a.vertices {
+                let on_edge = b.edges.iter().any(|e| {
+                    let c = distance::closest_on_segment(v, &e.vertices[0], &e.vertices[1]);
+                    c.subtract(v).length() <= eps
+                });
+                if on_edge || on_face(v) {
+                    candidates.push(*v);
+                }
+            }
+        }
+
+        for e in &self.edges {
+            for f in &other.edges {
+                let [p1, q1] = &e.vertices;
+                let [p2, q2] = &f.vertices;
+
+                let d1 = q1.subtract(p1);
+                let d2 = q2.subtract(p2);
+                if d1.cross_product(&d2).length() <= DEFAULT_EPS * d1.length() * d2.length() {
+                    continue;
+                }
+
+                let (c1, c2) = distance::closest_between_segments(p1, q1, p2, q2);
+                if c1.subtract(&c2).length() <= eps {
+                    candidates.push(c1.midpoint(&c2));
+                }
+            }
+        }
+
+        let mut points: Vec<Vertex> = Vec::new();
+        for c in candidates {
+            if !points.iter().any(|p| p.subtract(&c).length() <= eps) {
+                points.push(c);
+            }
+        }
+
+        points
+    }
+
+    /// A direction along which the projections of the two triangles don't
+    /// overlap, or `None` when no such direction exists because they
+    /// intersect or touch. Candidates are the two normals, the cross products
+    /// of edge pairs and, for coplanar pairs, the in-plane edge normals.
+    pub fn separating_axis(&self, other: &Triangle) -> Option<Vertex> {
+        let n1 = self.normal();
+        let n2 = other.normal();
+
+        let mut axes = vec![n1, n2];
+        for e in &self.edges {
+            for f in &other.edges {
+                axes.push(e.direction().cross_product(&f.direction()));
+            }
+        }
+        for e in self.edges.iter().chain(&other.edges) {
+            axes.push(n1.cross_product(&e.direction()));
+        }
+
+        let interval = |t: &Triangle, axis: &Vertex| {
+            let d = t.vertices.map(|v| v.dot_product(axis));
+            (d[0].min(d[1]).min(d[2]), d[0].max(d[1]).max(d[2]))
+        };
+
+        axes.into_iter()
+            .filter(|axis| axis.dot_product(axis) > 0.0)
+            .find(|axis| {
+                let (min_a, max_a) = interval(self, axis);
+                let (min_b, max_b) = interval(other, axis);
+                max_a < min_b || max_b < min_a
+            })
+    }
+
+    /// A score in [0, 1] for how much two triangles overlap:
+    ///
+    /// - 0.0 when they don't intersect, or only touch, e.g. at a vertex,
+    ///   along a shared edge or with an edge lying in the other's plane.
+    /// - For coplanar pairs, the area they share divided by the area of the
+    ///   smaller triangle, so 1.0 when one contains the other.
+    /// - For pairs passing through each other's interiors,
+    ///   `intersection_length` divided by the smaller of the two triangles'
+    ///   longest edges. The segment lies in both triangles, so it can't be
+    ///   longer than either's longest edge.
+    pub fn overlap_ratio(&self, other: &Triangle) -> f64 {
+        if !self.intersect(other) {
+            return 0.0;
+        }
+
+        if self.are_coplanar(other, DEFAULT_EPS * self.longest_edge()) {
+            // Projecting scales every area in the plane alike
+            let (a, axis) = self.project_2d();
+            let b = other.vertices.map(|v| planar::project(&v, axis));
+
+            let shared = planar::polygon_area(&planar::clip_to_triangle(&b, &a));
+            let smaller = planar::polygon_area(&a).min(planar::polygon_area(&b));
+
+            return if smaller > 0.0 {
+                (shared / smaller).min(1.0)
+            } else {
+                0.0
+            };
+        }
+
+        match self.intersection_length(other) {
+            Some(len) => (len / self.longest_edge().min(other.longest_edge())).min(1.0),
+            None => 0.0,
+        }
+    }
+
+    /// How the triangles intersect, or `None` when they don't. Agrees with
+    /// `intersect` except that a degenerate triangle always gives `None`, as
+    /// for `intersection_segment`, and so does a pair that `intersect` only
+    /// calls touching through rounding, with no point of contact to within
+    /// `DEFAULT_EPS`. `Touching` always holds at least one point.
+    pub fn intersection_detail(&self, other: &Triangle) -> Option<Intersection> {
+        if self.is_degenerate() || other.is_degenerate() || !self.intersect(other) {
+            return None;
+        }
+
+        let eps = DEFAULT_EPS * self.longest_edge();
+        let touching = || {
+            let points = self.contact_points(other, eps);
+            (!points.is_empty()).then_some(Intersection::Touching(points))
+        };
+
+        if self.are_coplanar(other, eps) {
+            let (a, axis) = self.project_2d();
+            let b = other.vertices.map(|v| planar::project(&v, axis));
+
+            let shared = planar::clip_to_triangle(&b, &a);
+            if planar::polygon_area(&shared) <= DEFAULT_EPS * planar::polygon_area(&a) {
+                return touching();
+            }
+
+            // Lift the overlap back onto this triangle's plane, solving n o p = d
+            // for the dropped coordinate
+            let (n, d) = self.plane_equation();
+            let lift = |(s, t): planar::Point2| match axis {
+                Axis::X => Vertex::new((d - n.y * s - n.z * t) / n.x, s, t),
+                Axis::Y => Vertex::new(t, (d - n.z * s - n.x * t) / n.y, s),
+                Axis::Z => Vertex::new(s, t, (d - n.x * s - n.y * t) / n.z),
+            };
+
+            return Some(Intersection::Coplanar(
+                shared.into_iter().map(lift).collect(),
+            ));
+        }
+
+        let segment = match self.intersection_segment(other) {
+            Some(segment) => segment,
+            None => return touching(),
+        };
+        let [lo, hi] = segment.vertices;
+
+        if self.is_crossing(other, &segment) {
+            Some(Intersection::Crossing(segment))
+        } else if lo.abs_diff_eq(&hi, eps) {
+            Some(Intersection::Touching(vec![lo]))
+        } else {
+            Some(Intersection::Touching(vec![lo, hi]))
+        }
+    }
+
+    /// `intersect` for anything convertible into a `Triangle`, e.g.
+    /// `t.intersects([a, b, c])`.
+    pub fn intersects(&self, t: impl Into<Triangle>) -> bool {
+        self.intersect(&t.into())
+    }
+
+    /// How many of the six edge tests in `intersect` succeed, 0 to 6.
+    pub fn crossing_count(&self, t: &Triangle) -> u8 {
+        let (self_plane, t_plane) = (self.plane_data(), t.plane_data());
+        let into_self = t
+            .edges
+            .iter()
+            .filter(|e| self.edge_intersect_plane(&self_plane, e));
+        let into_t = self
+            .edges
+            .iter()
+            .filter(|e| t.edge_intersect_plane(&t_plane, e));
+
+        (into_self.count() + into_t.count()) as u8
+    }
+}
+
+impl From<[Vertex; 3]> for Triangle {
+    fn from([u, v, w]: [Vertex; 3]) -> Triangle {
+        Triangle::new(u, v, w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /* Every vertex order of t, keeping and reversing its winding */
+    pub(crate) fn orderings(t: &Triangle) -> [Triangle; 6] {
+        let [u, v, w] = t.vertices;
+        [
+            Triangle::new(u, v, w),
+            Triangle::new(v, w, u),
+            Triangle::new(w, u, v),
+            Triangle::new(u, w, v),
+            Triangle::new(w, v, u),
+            Triangle::new(v, u, w),
+        ]
+    }
+
+    /* intersect gives `expected` for every ordering of a and b, both ways */
+    fn assert_intersect(a: &Triangle, b: &Triangle, expected: bool) {
+        for a in &orderings(a) {
+            for b in &orderings(b) {
+                assert_eq!(a.intersect(b), expected, "{:?} {:?}", a, b);
+                assert_eq!(b.intersect(a), expected, "{:?} {:?}", b, a);
+            }
+        }
+    }
+
+    #[test]
+    fn shared_vertex_touches() {
+        let a = triangle![[0, 0, 0], [1, 0, 0], [0, 1, 0]];
+        let b = triangle![[0, 0, 0], [-1, 0, -1], [0, -1, -1]];
+
+        assert_intersect(&a, &b, true);
+    }
+
+    #[test]
+    fn vertex_on_plane() {
+        let a = triangle![[0, 0, 0], [1, 0, 0], [0, 1, 0]];
+
+        let inside = triangle![[0.2, 0.2, 0], [0.2, 0.2, 1], [0.5, 0.5, 1]];
+        let on_edge = triangle![[0.5, 0, 0], [0.5, 0.5, 1], [0.5, -0.5, 1]];
+        let outside = triangle![[2, 2, 0], [2, 2, 1], [3, 3, 1]];
+
+        assert_intersect(&a, &inside, true);
+        assert_intersect(&a, &on_edge, true);
+        assert_intersect(&a, &outside, false);
+    }
+
+    #[test]
+    fn edge_on_plane() {
+        let a = triangle![[0, 0, 0], [1, 0, 0], [0, 1, 0]];
+
+        let across = triangle![[-1, 0.5, 0], [2, 0.5, 0], [0, 0.5, 1]];
+        let along_edge = triangle![[0, 0, 0], [1, 0, 0], [0, -1, 1]];
+        let beside = triangle![[-1, 2, 0], [2, 2, 0], [0, 2, 1]];
+
+        assert_intersect(&a, &across, true);
+        assert_intersect(&a, &along_edge, true);
+        assert_intersect(&a, &beside, false);
+    }
+
+    #[test]
+    fn segments_intersecting_mixed() {
+        let t = triangle![[0, 0, 0], [1, 0, 0], [0, 1, 0]];
+        let segs = [
+            Edge::new(Vertex::new(0.2, 0.2, -1.0), Vertex::new(0.2, 0.2, 1.0)),
+            Edge::new(Vertex::new(2.0, 2.0, -1.0), Vertex::new(2.0, 2.0, 1.0)),
+            Edge::new(Vertex::new(0.2, 0.2, 0.5), Vertex::new(0.2, 0.2, 1.0)),
+            Edge::new(Vertex::new(-1.0, 0.3, 0.0), Vertex::new(2.0, 0.3, 0.0)),
+            Edge::new(Vertex::new(0.5, 0.5, 0.0), Vertex::new(0.5, 0.5, 1.0)),
+            Edge::new(Vertex::new(-1.0, 3.0, 0.0), Vertex::new(2.0, 3.0, 0.0)),
+        ];
+
+        assert_eq!(t.segments_intersecting(&segs), vec![0, 3, 4]);
+
+        let one_by_one: Vec<usize> = (0..segs.len())
+            .filter(|&i| t.edge_intersect(&segs[i]))
+            .collect();
+        assert_eq!(one_by_one, vec![0, 3, 4]);
+    }
+
+    #[test]
+    fn intersect_uses_default_eps() {
+        let a = triangle![[0, 0, 0], [1, 0, 0], [0, 1, 0]];
+        let others = [
+            triangle![[0.2, 0.2, -1], [0.2, 0.2, 1], [0.6, 0.1, 0]],
+            triangle![[0, 0, 0], [-1, 0, -1], [0, -1, -1]],
+            triangle![[0.2, 0.2, 1e-12], [2, 0.2, 0], [0.2, 2, 0]],
+            triangle![[0.5, 0.5, 1e-3], [2, 0.5, 1e-3], [0.5, 2, 1e-3]],
+            triangle![[5, 5, 5], [6, 5, 5], [5, 6, 5]],
+        ];
+
+        for b in &others {
+            assert_eq!(a.intersect(b), a.intersect_eps(b, DEFAULT_EPS), "{:?}", b);
+        }
+
+        // Loose enough to call the parallel triangle 1e-3 above coplanar
+        assert!(!a.intersect(&others[3]));
+        assert!(a.intersect_eps(&others[3], 1e-2));
+    }
+
+    #[test]
+    fn default_eps_scales_with_longest_edge() {
+        let small = triangle![[0, 0, 0], [1, 0, 0], [0, 1, 0]];
+        let large = triangle![[0, 0, 0], [100, 0, 0], [0, 1, 0]];
+
+        assert_eq!(default_eps_for(&[]), 0.0);
+        assert_eq!(default_eps_for(&[&small]), DEFAULT_EPS * 2f64.sqrt());
+        assert_eq!(
+            default_eps_for(&[&small, &large]),
+            DEFAULT_EPS * 10001f64.sqrt()
+        );
+    }
+
+    #[test]
+    fn edge_in_plane_decided_in_2d() {
+        let t = triangle![[0, 0, 0], [1, 0, 0], [0, 1, 0]];
+        let edge = |p: [f64; 3], q: [f64; 3]| Edge::new(p.into(), q.into());
+
+        assert!(t.edge_intersect(&edge([-1.0, 0.3, 0.0], [2.0, 0.3, 0.0])));
+        assert!(t.edge_intersect(&edge([0.1, 0.1, 0.0], [0.3, 0.1, 0.0])));
+        assert!(t.edge_intersect(&edge([0.5, 0.0, 0.0], [2.0, 0.0, 0.0])));
+        assert!(!t.edge_intersect(&edge([-1.0, 2.0, 0.0], [2.0, 2.0, 0.0])));
+        assert!(!t.edge_intersect(&edge([0.6, 0.6, 0.0], [2.0, 0.6, 0.0])));
+
+        // Within the tolerance of the plane still counts as in it
+        assert!(t.edge_intersect(&edge([0.1, 0.1, 1e-12], [0.3, 0.1, -1e-12])));
+
+        let crosses = triangle![[-1, 0.3, 0], [2, 0.3, 0], [0, 0.3, 1]];
+        let misses = triangle![[0.6, 0.6, 0], [2, 0.6, 0], [1, 0.6, 1]];
+        assert!(t.intersect(&crosses) && crosses.intersect(&t));
+        assert!(!t.intersect(&misses) && !misses.intersect(&t));
+    }
+
+    #[test]
+    fn coplanar_dispatch() {
+        let t = triangle![[0, 0, 0], [4, 0, 0], [0, 4, 0]];
+
+        // Strictly inside, so no edge test could find it
+        let inside = triangle![[0.5, 0.5, 0], [1, 0.5, 0], [0.5, 1, 0]];
+        let apart = triangle![[5, 5, 0], [6, 5, 0], [5, 6, 0]];
+        let tilted = triangle![[5, 5, 1e-12], [6, 5, 0], [5, 6, 0]];
+        let skew = triangle![[1, 1, -1], [1, 1, 1], [2, 0.5, 0]];
+
+        for c in [&inside, &apart, &tilted] {
+            assert!(t.are_coplanar(c, 1e-9));
+        }
+        assert!(!t.are_coplanar(&skew, 1e-9));
+
+        assert!(t.intersect(&inside) && inside.intersect(&t));
+        assert!(!t.intersect(&apart) && !apart.intersect(&t));
+        assert!(!t.intersect(&tilted) && !tilted.intersect(&t));
+        assert!(t.intersect(&skew) && skew.intersect(&t));
+    }
+
+    /*
+     * Sampling oracle: points on a barycentric grid with `N` steps per edge,
+     * so every point of the triangle is within longest_edge / N of one.
+     */
+    const N: usize = 24;
+
+    fn samples(t: &Triangle) -> Vec<Vertex> {
+        let [a, b, c] = t.vertices;
+        let mut points = Vec::new();
+
+        for i in 0..=N {
+            for j in 0..=(N - i) {
+                let (u, v) = (i as f64 / N as f64, j as f64 / N as f64);
+                points.push(a.scale(1.0 - u - v).add(&b.scale(u)).add(&c.scale(v)));
+            }
+        }
+
+        points
+    }
+
+    /*
+     * Two points of `a` on opposite sides of the plane of `b`, both over the
+     * inside of `b`, prove a crossing: the segment between them stays in `a`
+     * and over `b`, so it meets `b` where it passes the plane.
+     */
+    fn proves_crossing(a: &Triangle, b: &Triangle) -> bool {
+        let [p, q, r] = b.vertices;
+        let n = q.subtract(&p).cross_product(&r.subtract(&p));
+        let n_sq = n.dot_product(&n);
+        let margin = 1e-6 * n_sq.sqrt();
+
+        let over_b = |x: &Vertex| {
+            [(&p, &q), (&q, &r), (&r, &p)]
+                .iter()
+                .all(|(u, v)| v.subtract(u).cross_product(&x.subtract(u)).dot_product(&n) > 0.0)
+        };
+        let heights: Vec<f64> = samples(a)
+            .iter()
+            .filter(|x| over_b(x))
+            .map(|x| n.dot_product(&x.subtract(&p)))
+            .collect();
+
+        heights.iter().any(|&h| h > margin) && heights.iter().any(|&h| h < -margin)
+    }
+
+    /* Closest pair of samples, which is at least the true distance */
+    fn sampled_distance(a: &Triangle, b: &Triangle) -> f64 {
+        let (sa, sb) = (samples(a), samples(b));
+
+        sa.iter()
+            .flat_map(|x| sb.iter().map(move |y| x.subtract(y).length()))
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    #[test]
+    fn sampling_oracle() {
+        let mut rng = jitter::XorShift(0x2545_f491_4f6c_dd1d);
+        let mut vertex = || Vertex::new(rng.unit(), rng.unit(), rng.unit());
+
+        let (mut decided, mut crossing) = (0, 0);
+        for _ in 0..400 {
+            let a = Triangle::new(vertex(), vertex(), vertex());
+            let b = Triangle::new(vertex(), vertex(), vertex());
+            let got = a.intersect(&b);
+
+            // Intersecting triangles have samples within one covering radius
+            // of each, so any farther apart can't intersect
+            let reach = (a.longest_edge() + b.longest_edge()) / N as f64;
+            if sampled_distance(&a, &b) > reach {
+                assert!(!got, "oracle says apart: {:?} {:?}", a, b);
+                decided += 1;
+            } else if proves_crossing(&a, &b) || proves_crossing(&b, &a) {
+                assert!(got, "oracle says crossing: {:?} {:?}", a, b);
+                decided += 1;
+                crossing += 1;
+            }
+        }
+
+        // Most pairs should be clear cut, with both verdicts represented
+        assert!(decided >= 300, "only {} of 400 pairs decided", decided);
+        assert!(crossing >= 80, "only {} crossing pairs", crossing);
+    }
+
+    #[test]
+    fn overlap_ratio_scores() {
+        let t = triangle![[0, 0, 0], [4, 0, 0], [0, 4, 0]];
+
+        let contained = triangle![[0.5, 0.5, 0], [1, 0.5, 0], [0.5, 1, 0]];
+        let disjoint = triangle![[5, 5, 5], [6, 5, 5], [5, 6, 5]];
+        assert_eq!(t.overlap_ratio(&contained), 1.0);
+        assert_eq!(contained.overlap_ratio(&t), 1.0);
+        assert_eq!(t.overlap_ratio(&disjoint), 0.0);
+
+        // Touching only: a hinge at 90 degrees, a T-junction and a vertex
+        let hinge = triangle![[0, 0, 0], [4, 0, 0], [0, 0, 4]];
+        let t_junction = triangle![[1, 1, 0], [2, 1, 0], [1.5, 1, 3]];
+        let vertex = triangle![[1, 1, 0], [1, 1, 2], [2, 2, 2]];
+        for touching in [&hinge, &t_junction, &vertex] {
+            assert_eq!(t.overlap_ratio(touching), 0.0, "{:?}", touching);
+            assert_eq!(touching.overlap_ratio(&t), 0.0, "{:?}", touching);
+        }
+
+        // Crosses along x = 1 from y = 0 to y = 3, over the shorter of the
+        // longest edges, 4 sqrt(2)
+        let crossing = triangle![[1, 0, -1], [1, 0, 1], [1, 6, 0]];
+        let expected = 3.0 / (4.0 * 2f64.sqrt());
+        assert!((t.overlap_ratio(&crossing) - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn intersection_length_only_for_crossings() {
+        let t = triangle![[0, 0, 0], [4, 0, 0], [0, 4, 0]];
+
+        let crossing = triangle![[1, 0, -1], [1, 0, 1], [1, 6, 0]];
+        let len = t.intersection_length(&crossing).unwrap();
+        assert!((len - 3.0).abs() < 1e-12);
+
+        let hinge = triangle![[0, 0, 0], [4, 0, 0], [0, 0, 4]];
+        let vertex = triangle![[1, 1, 0], [1, 1, 2], [2, 2, 2]];
+        let disjoint = triangle![[5, 5, 5], [6, 5, 5], [5, 6, 5]];
+        for other in [&hinge, &vertex, &disjoint] {
+            assert_eq!(t.intersection_length(other), None, "{:?}", other);
+            assert_eq!(other.intersection_length(&t), None, "{:?}", other);
+        }
+    }
+
+    #[test]
+    fn intersection_detail_variants() {
+        let t = triangle![[0, 0, 0], [4, 0, 0], [0, 4, 0]];
+        let detail = |other: &Triangle| t.intersection_detail(other);
+
+        match detail(&triangle![[1, 0, -1], [1, 0, 1], [1, 6, 0]]) {
+            Some(Intersection::Crossing(e)) => {
+                assert!((e.direction().length() - 3.0).abs() < 1e-12)
+            }
+            other => panic!("expected Crossing, got {:?}", other),
+        }
+
+        match detail(&triangle![[1, 1, 0], [5, 1, 0], [1, 5, 0]]) {
+            Some(Intersection::Coplanar(poly)) => {
+                // The triangle (1, 1), (3, 1), (1, 3) cut off by x + y = 4
+                let flat: Vec<_> = poly.iter().map(|v| (v.x, v.y)).collect();
+                assert!((planar::polygon_area(&flat) - 2.0).abs() < 1e-12);
+                assert!(poly.iter().all(|v| v.z == 0.0));
+            }
+            other => panic!("expected Coplanar, got {:?}", other),
+        }
+
+        match detail(&triangle![[1, 1, 0], [1, 1, 2], [2, 2, 2]]) {
+            Some(Intersection::Touching(points)) => {
+                assert_eq!(points.len(), 1);
+                assert!(points[0].abs_diff_eq(&Vertex::new(1.0, 1.0, 0.0), 1e-12));
+            }
+            other => panic!("expected Touching, got {:?}", other),
+        }
+
+        // A hinge along the edge from (0, 0, 0) to (4, 0, 0), and a coplanar
+        // neighbour across the edge x + y = 4
+        for other in [
+            triangle![[0, 0, 0], [4, 0, 0], [0, 0, 4]],
+            triangle![[4, 0, 0], [0, 4, 0], [4, 4, 0]],
+        ] {
+            match detail(&other) {
+                Some(Intersection::Touching(points)) => assert_eq!(points.len(), 2),
+                other => panic!("expected Touching, got {:?}", other),
+            }
+        }
+
+        // An edge within the in-plane tolerance above the face, touching
+        // where an exact computation would find no contact
+        let a = triangle![[0, 0, 0], [1, 0, 0], [0, 1, 0]];
+        let b = triangle![[0.1, 0.1, 1e-12], [0.3, 0.1, 1e-12], [0.2, 0.2, 1]];
+        assert!(a.intersect(&b) && b.intersect(&a));
+        for detail in [a.intersection_detail(&b), b.intersection_detail(&a)] {
+            match detail {
+                Some(Intersection::Touching(points)) => {
+                    assert_eq!(points.len(), 2, "{:?}", points);
+                    for e in [Vertex::new(0.1, 0.1, 0.0), Vertex::new(0.3, 0.1, 0.0)] {
+                        assert!(points.iter().any(|p| p.abs_diff_eq(&e, 1e-9)));
+                    }
+                }
+                other => panic!("expected Touching, got {:?}", other),
+            }
+        }
+        assert_eq!(a.contact_points(&b, 1e-9).len(), 2);
+
+        // Only the vertex that close doesn't count as touching, in either
+        let vertex = triangle![[0.2, 0.2, 1e-12], [0.2, 0.2, 1], [0.5, 0.5, 1]];
+        assert!(!a.intersect(&vertex));
+        assert!(a.intersection_detail(&vertex).is_none());
+
+        assert!(detail(&triangle![[5, 5, 5], [6, 5, 5], [5, 6, 5]]).is_none());
+
+        // Degenerate, so None even though it pierces t
+        let needle = triangle![[0, 0, -1], [1, 1, 1], [2, 2, 3]];
+        assert!(t.intersect(&needle));
+        assert!(detail(&needle).is_none());
+    }
+
+    #[test]
+    fn project_2d_drops_dominant_axis() {
+        for (t, axis) in [
+            (triangle![[0, 0, 0], [1, 0, 0], [0, 1, 0.2]], Axis::Z),
+            (triangle![[0, 0, 0], [0, 1, 0], [0.3, 0, 1]], Axis::X),
+            (triangle![[0, 0, 0], [1, 0.5, 0], [0, -0.2, 1]], Axis::Y),
+        ] {
+            let n = t.normal();
+            let (projected, dropped) = t.project_2d();
+            assert_eq!(dropped, axis);
+            assert!([Axis::X, Axis::Y, Axis::Z]
+                .iter()
+                .all(|&a| n.component(a).abs() <= n.component(dropped).abs()));
+            assert_eq!(
+                projected,
+                t.vertices().map(|v| planar::project(&v, dropped))
+            );
+        }
+    }
+
+    #[test]
+    fn approximate_equality() {
+        let a = Vertex::new(1.0, -2.0, 1000.0);
+
+        assert!(a.abs_diff_eq(&Vertex::new(1.0 + 1e-10, -2.0, 1000.0 - 1e-10), 1e-9));
+        assert!(!a.abs_diff_eq(&Vertex::new(1.0, -2.0 + 1e-8, 1000.0), 1e-9));
+
+        // 1e-7 apart is far outside epsilon, but only 1e-10 relative to 1000
+        let b = Vertex::new(1.0, -2.0, 1000.0 + 1e-7);
+        assert!(!a.abs_diff_eq(&b, 1e-9));
+        assert!(a.relative_eq(&b, 1e-9, 1e-9));
+        assert!(!a.relative_eq(&b, 1e-9, 1e-12));
+
+        // Near zero, only epsilon helps
+        let c = Vertex::new(1e-12, 0.0, 0.0);
+        let zero = Vertex::new(0.0, 0.0, 0.0);
+        assert!(zero.relative_eq(&c, 1e-9, 1e-12));
+        assert!(!zero.relative_eq(&c, 0.0, 0.5));
+    }
+
+    #[test]
+    fn plane_equation_holds_at_vertices() {
+        for t in [
+            triangle![[0, 0, 5], [1, 0, 5], [0, 1, 5]],
+            triangle![[1, 2, 3], [-4, 0.5, 2], [3, -1, -7]],
+            triangle![[100, 0, 0], [100, 1, 1e-3], [99, 0, 2]],
+        ] {
+            let (n, d) = t.plane_equation();
+            assert!((n.length() - 1.0).abs() < 1e-12);
+            for v in t.vertices() {
+                assert!((n.dot_product(v) - d).abs() < 1e-9, "{:?}", t);
+            }
+        }
+
+        let (n, d) = triangle![[0, 0, 5], [1, 0, 5], [0, 1, 5]].plane_equation();
+        assert_eq!((n, d), (Vertex::new(0.0, 0.0, 1.0), 5.0));
+    }
+
+    #[test]
+    fn crossing_count_two_edges() {
+        let a = triangle![[0, 0, 0], [1, 0, 0], [0, 1, 0]];
+
+        // b's vertical edge pierces a, and a's edge from (1, 0, 0) to
+        // (0, 1, 0) pierces b at (0.5, 0.5, 0). The other edges only reach
+        // the planes outside the triangles.
+        let b = triangle![[0.2, 0.2, -1], [0.2, 0.2, 1], [5, 5, 0]];
+        assert_eq!(a.crossing_count(&b), 2);
+        assert_eq!(b.crossing_count(&a), 2);
+
+        assert_eq!(
+            a.crossing_count(&triangle![[5, 5, 5], [6, 5, 5], [5, 6, 5]]),
+            0
+        );
+    }
+
+    #[test]
+    fn project_onto_and_reflect() {
+        let v = Vertex::new(3.0, 4.0, 0.0);
+        let x = Vertex::new(2.0, 0.0, 0.0);
+        let z = Vertex::new(0.0, 0.0, 5.0);
+
+        // Orthogonal: nothing along the other vector, and reflection is a no-op
+        assert_eq!(v.project_onto(&z), Vertex::new(0.0, 0.0, 0.0));
+        assert_eq!(v.reflect(&z), v);
+
+        // Parallel: all of it, and reflection reverses it
+        let along = Vertex::new(-6.0, 0.0, 0.0);
+        assert_eq!(along.project_onto(&x), along);
+        assert_eq!(along.reflect(&x), Vertex::new(6.0, 0.0, 0.0));
+
+        // In between: the normal's length doesn't matter
+        assert_eq!(v.project_onto(&x), Vertex::new(3.0, 0.0, 0.0));
+        assert_eq!(v.reflect(&x), Vertex::new(-3.0, 4.0, 0.0));
+    }
+
+    #[test]
+    fn winding_by_view() {
+        // Counterclockwise in XY, so its normal points up +z
+        let t = triangle![[0, 0, 0], [1, 0, 0], [0, 1, 0]];
+
+        let toward = Vertex::new(0.0, 0.0, -1.0);
+        let away = Vertex::new(0.0, 0.0, 1.0);
+        assert_eq!(t.winding(&toward), Winding::CounterClockwise);
+        assert_eq!(t.winding(&away), Winding::Clockwise);
+        assert_eq!(t.winding(&Vertex::new(1.0, 1.0, 0.0)), Winding::EdgeOn);
+
+        let flipped = triangle![[0, 0, 0], [0, 1, 0], [1, 0, 0]];
+        assert_eq!(flipped.winding(&toward), Winding::Clockwise);
+    }
+
+    #[test]
+    fn midpoints() {
+        let t = triangle![[0, 0, 0], [4, 0, 2], [0, 6, -2]];
+
+        assert_eq!(
+            Vertex::new(1.0, 2.0, 3.0).midpoint(&Vertex::new(3.0, -2.0, 4.0)),
+            Vertex::new(2.0, 0.0, 3.5)
+        );
+        assert_eq!(
+            t.edge_midpoints(),
+            [
+                Vertex::new(2.0, 0.0, 1.0),
+                Vertex::new(2.0, 3.0, 0.0),
+                Vertex::new(0.0, 3.0, -1.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn line_intersect_both_ways() {
+        let t = triangle![[0, 0, 0], [1, 0, 0], [0, 1, 0]];
+        let up = Vertex::new(0.0, 0.0, 1.0);
+
+        // The triangle is behind the point, which a ray would miss
+        let behind = Vertex::new(0.25, 0.25, 2.0);
+        let hit = t.line_intersect(&behind, &up).unwrap();
+        assert!(hit.abs_diff_eq(&Vertex::new(0.25, 0.25, 0.0), 1e-12));
+        assert_eq!(t.line_intersect(&behind, &up.scale(-1.0)), Some(hit));
+
+        // Parallel to the plane, in it or above it
+        let along = Vertex::new(1.0, 1.0, 0.0);
+        assert_eq!(t.line_intersect(&Vertex::new(0.1, 0.1, 1.0), &along), None);
+        assert_eq!(t.line_intersect(&Vertex::new(0.1, 0.1, 0.0), &along), None);
+
+        // Through the plane beside the triangle
+        assert_eq!(t.line_intersect(&Vertex::new(1.0, 1.0, 2.0), &up), None);
+    }
+
+    #[test]
+    fn shrinking_separates_a_crossing_pair() {
+        let a = triangle![[0, 0, 0], [4, 0, 0], [0, 4, 0]];
+        let b = triangle![[1, 0, -1], [1, 0, 1], [1, 6, 0]];
+        assert!(a.intersect(&b));
+
+        let small = a.scale_about_centroid(0.1);
+        assert!(small.centroid().abs_diff_eq(&a.centroid(), 1e-12));
+        assert!((small.longest_edge() - 0.1 * a.longest_edge()).abs() < 1e-12);
+        assert_eq!(a.scale_about_centroid(1.0).vertices(), a.vertices());
+
+        // The centroids lie apart, at x = 4/3 and x = 1, so the shrunk copies
+        // no longer reach each other
+        let ratio = a.overlap_ratio(&b);
+        let (a, b) = (a.scale_about_centroid(0.1), b.scale_about_centroid(0.1));
+        assert!(0.1 < ratio);
+        assert!(!a.intersect(&b) && !b.intersect(&a));
+        assert_eq!(a.overlap_ratio(&b), 0.0);
+    }
+
+    #[test]
+    fn intersects_takes_conversions() {
+        let t = triangle![[0, 0, 0], [1, 0, 0], [0, 1, 0]];
+        let c = |x: f64, y: f64, z: f64| Vertex::new(x, y, z);
+
+        assert!(t.intersects([c(0.2, 0.2, -1.0), c(0.2, 0.2, 1.0), c(0.6, 0.1, 0.0)]));
+        assert!(!t.intersects([c(5.0, 5.0, 5.0), c(6.0, 5.0, 5.0), c(5.0, 6.0, 5.0)]));
+
+        // From coordinate arrays, by way of Vertex
+        let arrays = [[0.2, 0.2, -1.0], [0.2, 0.2, 1.0], [0.6, 0.1, 0.0]];
+        assert!(t.intersects(arrays.map(Vertex::from)));
+
+        let other = triangle![[0.2, 0.2, -1], [0.2, 0.2, 1], [0.6, 0.1, 0]];
+        assert_eq!(t.intersects(other.clone()), t.intersect(&other));
+    }
+
+    #[test]
+    fn clip_to_unit_box() {
+        let (min, max) = (Vertex::new(0.0, 0.0, 0.0), Vertex::new(1.0, 1.0, 1.0));
+        let area = |tris: &[Triangle]| tris.iter().map(|t| t.normal().length() / 2.0).sum::<f64>();
+        let clip = |t: &Triangle| t.clip_to_aabb(&min, &max);
+
+        // Cut off by x = 1 and y = 1, leaving the unit square
+        let large = triangle![[0, 0, 0], [2, 0, 0], [0, 2, 0]];
+        assert!((area(&clip(&large)) - 1.0).abs() < 1e-12);
+
+        // Straddling x = 1 only, losing a corner of legs 0.5
+        let straddle = triangle![[0.5, 0, 0.5], [1.5, 0, 0.5], [0.5, 1, 0.5]];
+        assert!((area(&clip(&straddle)) - 0.375).abs() < 1e-12);
+        assert!(clip(&straddle)
+            .iter()
+            .flat_map(|t| t.vertices())
+            .all(|v| v.x <= 1.0));
+
+        let inside = triangle![[0.1, 0.1, 0.1], [0.9, 0.1, 0.5], [0.1, 0.9, 0.9]];
+        assert_eq!(clip(&inside).len(), 1);
+        assert_eq!(clip(&inside)[0].vertices(), inside.vertices());
+
+        let outside = triangle![[2, 2, 2], [3, 2, 2], [2, 3, 2]];
+        assert!(clip(&outside).is_empty());
+
+        // Meeting the box only at its corner (1, 0, 0), or along its face x = 1
+        let corner = triangle![[1, 0, 0], [2, -1, 0], [2, 0, -1]];
+        let face = triangle![[1, 0, 0], [1, 1, 0], [2, 0.5, 0]];
+        assert!(clip(&corner).is_empty());
+        assert!(clip(&face).is_empty());
+    }
+
+    #[test]
+    fn merge_coplanar_quads() {
+        let a = triangle![[0, 0, 0], [1, 0, 0], [1, 1, 0]];
+        let b = triangle![[0, 0, 0], [1, 1, 0], [0, 1, 0]];
+        let v = |x: f64, y: f64| Vertex::new(x, y, 0.0);
+
+        // The unit square, counterclockwise like a
+        let quad = a.merge_coplanar(&b, 1e-9).unwrap();
+        assert_eq!(quad, [v(1.0, 1.0), v(0.0, 1.0), v(0.0, 0.0), v(1.0, 0.0)]);
+        assert_eq!(
+            b.merge_coplanar(&a, 1e-9).unwrap(),
+            [v(0.0, 0.0), v(1.0, 0.0), v(1.0, 1.0), v(0.0, 1.0)]
+        );
+
+        // A dart, reflex at (0.5, 0.5)
+        let c = triangle![[0, 0, 0], [2, 0, 0], [0.5, 0.5, 0]];
+        let d = triangle![[0, 0, 0], [0.5, 0.5, 0], [0, 2, 0]];
+        assert_eq!(c.merge_coplanar(&d, 1e-9), None);
+
+        // Folded along the shared edge, or sharing only a vertex
+        let folded = triangle![[0, 0, 0], [1, 1, 0], [0, 1, 1]];
+        let corner = triangle![[1, 1, 0], [2, 1, 0], [2, 2, 0]];
+        assert_eq!(a.merge_coplanar(&folded, 1e-9), None);
+        assert_eq!(a.merge_coplanar(&corner, 1e-9), None);
+    }
+
+    #[test]
+    fn barycentric_round_trip() {
+        let t = triangle![[1, 2, 3], [-4, 0.5, 2], [3, -1, -7]];
+
+        for (u, v, w) in [(0.2, 0.3, 0.5), (1.0, 0.0, 0.0), (-0.5, 1.25, 0.25)] {
+            let p = t.point_from_barycentric(u, v, w);
+            let (bu, bv, bw) = t.barycentric(&p);
+            assert!((bu - u).abs() < 1e-12 && (bv - v).abs() < 1e-12 && (bw - w).abs() < 1e-12);
+            assert!(t.point_from_barycentric(bu, bv, bw).abs_diff_eq(&p, 1e-12));
+        }
+
+        assert_eq!(t.point_from_barycentric(0.0, 0.0, 1.0), t.vertices()[2]);
+    }
+
+    #[test]
+    fn contains_point_2d_ignores_z() {
+        // Tilted out of the XY plane, which contains_point_2d doesn't care about
+        let t = triangle![[0, 0, 5], [2, 0, -1], [0, 2, 3]];
+
+        assert!(t.contains_point_2d(0.5, 0.5));
+        assert!(t.contains_point_2d(1.0, 0.0));
+        assert!(t.contains_point_2d(1.0, 1.0));
+        assert!(t.contains_point_2d(0.0, 0.0));
+        assert!(!t.contains_point_2d(1.5, 1.5));
+        assert!(!t.contains_point_2d(-0.1, 0.5));
+
+        // The winding doesn't matter either
+        let flipped = triangle![[0, 0, 0], [0, 2, 0], [2, 0, 0]];
+        assert!(flipped.contains_point_2d(0.5, 0.5));
+        assert!(!flipped.contains_point_2d(3.0, 0.0));
+    }
+
+    #[test]
+    fn rounded_segment_is_bit_stable() {
+        let a = triangle![[0, 0, 0], [3, 0, 0], [0, 3, 0]];
+        let b = triangle![[0.1, 0.2, -1], [0.7, 0.3, 1], [0.2, 2, 0.3]];
+
+        let segment = a.intersection_segment(&b).unwrap();
+        let rounded = segment
+            .vertices
+            .map(|v| v.round_to(6).to_array().map(f64::to_bits));
+        let expected = [[0.176923, 1.584615, 0.0], [0.4, 0.25, 0.0]].map(|v| v.map(f64::to_bits));
+        assert_eq!(rounded, expected);
+
+        // Tiny negative coordinates round to 0.0, not -0.0
+        let v = Vertex::new(-1e-9, 2.0000004, -0.0).round_to(6);
+        assert_eq!(
+            v.to_array().map(f64::to_bits),
+            [0.0, 2.0, 0.0].map(f64::to_bits)
+        );
+    }
+
+    #[test]
+    fn index_and_component() {
+        let v = Vertex::new(1.5, -2.0, 7.0);
+
+        assert_eq!([v[0], v[1], v[2]], [1.5, -2.0, 7.0]);
+        assert_eq!(
+            [Axis::X, Axis::Y, Axis::Z].map(|a| v.component(a)),
+            [1.5, -2.0, 7.0]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Vertex index out of range: 3")]
+    fn index_out_of_range() {
+        let _ = Vertex::new(0.0, 0.0, 0.0)[3];
+    }
+
+    #[test]
+    fn centroid_distance_orders_pairs() {
+        let t = triangle![[0, 0, 0], [3, 0, 0], [0, 3, 0]];
+        let near = triangle![[0, 0, 1], [3, 0, 1], [0, 3, 1]];
+        let far = triangle![[3, 4, 0], [6, 4, 0], [3, 7, 0]];
+
+        assert_eq!(t.centroid_distance(&near), 1.0);
+        assert_eq!(t.centroid_distance(&far), 5.0);
+        assert_eq!(far.centroid_distance(&t), 5.0);
+        assert_eq!(t.centroid_distance(&t), 0.0);
+    }
+
+    #[test]
+    fn angle_classes() {
+        let right = triangle![[0, 0, 0], [3, 0, 0], [0, 4, 0]];
+        let obtuse = triangle![[0, 0, 0], [4, 0, 0], [-1, 1, 0]];
+        let acute = triangle![[0, 0, 0], [2, 0, 0], [1, 3f64.sqrt(), 0]];
+
+        let classes = |t: &Triangle, eps: f64| (t.is_acute(eps), t.is_right(eps), t.is_obtuse(eps));
+        assert_eq!(classes(&right, 1e-9), (false, true, false));
+        assert_eq!(classes(&obtuse, 1e-9), (false, false, true));
+        assert_eq!(classes(&acute, 1e-9), (true, false, false));
+
+        // A slightly obtuse triangle is right within a loose enough eps
+        let nearly = triangle![[0, 0, 0], [3, 0, 0], [-1e-3, 4, 0]];
+        assert_eq!(classes(&nearly, 1e-9), (false, false, true));
+        assert_eq!(classes(&nearly, 1e-2), (false, true, false));
+
+        let angles = right.interior_angles();
+        assert!((angles.iter().sum::<f64>() - std::f64::consts::PI).abs() < 1e-12);
+        assert!((angles[0] - FRAC_PI_2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn contact_points_shared_vertex_and_edge() {
+        let a = triangle![[0, 0, 0], [1, 0, 0], [0, 1, 0]];
+        let origin = Vertex::new(0.0, 0.0, 0.0);
+        let has = |points: &[Vertex], p: Vertex| points.iter().any(|q| q.abs_diff_eq(&p, 1e-12));
+
+        let vertex = triangle![[0, 0, 0], [-1, 0, -1], [0, -1, -1]];
+        assert_eq!(a.contact_points(&vertex, 1e-9), vec![origin]);
+        assert_eq!(vertex.contact_points(&a, 1e-9), vec![origin]);
+
+        let hinge = triangle![[0, 0, 0], [1, 0, 0], [0, 0, 1]];
+        for points in [
+            a.contact_points(&hinge, 1e-9),
+            hinge.contact_points(&a, 1e-9),
+        ] {
+            assert_eq!(points.len(), 2, "{:?}", points);
+            assert!(has(&points, origin) && has(&points, Vertex::new(1.0, 0.0, 0.0)));
+        }
+
+        // A vertex on the middle of an edge, and two triangles apart
+        let t_junction = triangle![[0.5, 0, 0], [0.5, -1, 1], [0.5, -1, -1]];
+        assert_eq!(
+            a.contact_points(&t_junction, 1e-9),
+            vec![Vertex::new(0.5, 0.0, 0.0)]
+        );
+        assert!(a
+            .contact_points(&triangle![[5, 5, 5], [6, 5, 5], [5, 6, 5]], 1e-9)
+            .is_empty());
+    }
+
+    #[test]
+    fn separating_axis_separates() {
+        let a = triangle![[0, 0, 0], [1, 0, 0], [0, 1, 0]];
+        let interval = |t: &Triangle, axis: &Vertex| {
+            let d = t.vertices().map(|v| v.dot_product(axis));
+            (d[0].min(d[1]).min(d[2]), d[0].max(d[1]).max(d[2]))
+        };
+
+        for b in [
+            triangle![[0, 0, 1], [1, 0, 1], [0, 1, 1]],
+            triangle![[2, 2, 0], [3, 2, 0], [2, 3, 0]],
+            triangle![[0.6, 0.6, -1], [0.6, 0.6, 1], [2, 2, 0]],
+            triangle![[1, 1, -1], [2, -1, 1], [-1, 2, 3]],
+        ] {
+            assert!(!a.intersect(&b));
+            let axis = a.separating_axis(&b).unwrap_or_else(|| panic!("{:?}", b));
+            let (min_a, max_a) = interval(&a, &axis);
+            let (min_b, max_b) = interval(&b, &axis);
+            assert!(max_a < min_b || max_b < min_a, "{:?} {:?}", b, axis);
+        }
+
+        let crossing = triangle![[0.2, 0.2, -1], [0.2, 0.2, 1], [0.6, 0.1, 0]];
+        let vertex = triangle![[0, 0, 0], [-1, 0, -1], [0, -1, -1]];
+        assert_eq!(a.separating_axis(&crossing), None);
+        assert_eq!(a.separating_axis(&vertex), None);
+    }
+
+    #[test]
+    fn centers_of_a_right_triangle() {
+        let t = triangle![[0, 0, 0], [4, 0, 0], [0, 3, 0]];
+
+        // The circumcenter is the hypotenuse's midpoint; the inradius is
+        // (3 + 4 - 5) / 2 = 1
+        let c = t.circumcenter().unwrap();
+        assert!(c.abs_diff_eq(&Vertex::new(2.0, 1.5, 0.0), 1e-12), "{:?}", c);
+        assert!(t.incenter().abs_diff_eq(&Vertex::new(1.0, 1.0, 0.0), 1e-12));
+
+        // The same up in 3D, equidistant from every vertex
+        let tilted = triangle![[1, 2, 3], [-4, 0.5, 2], [3, -1, -7]];
+        let c = tilted.circumcenter().unwrap();
+        let r = tilted.vertices().map(|v| v.subtract(&c).length());
+        assert!((r[0] - r[1]).abs() < 1e-9 && (r[1] - r[2]).abs() < 1e-9);
+
+        assert_eq!(
+            triangle![[0, 0, 0], [1, 1, 1], [2, 2, 2]].circumcenter(),
+            None
+        );
+    }
+
+    #[test]
+    fn snapping_restores_coplanarity() {
+        let a = triangle![[0, 0, 0], [1, 0, 0], [0, 1, 0]];
+        let noisy = triangle![
+            [0.2, 0.2, 1e-7],
+            [0.9999999, 0.2, 0],
+            [0.2, 0.9999999, -1e-7]
+        ];
+        let eps = default_eps_for(&[&a]);
+
+        assert!(!a.are_coplanar(&noisy, eps));
+
+        let snapped = noisy.snap_coords(1e-3);
+        assert!(a.are_coplanar(&snapped, eps));
+        assert_eq!(snapped.vertices()[1], Vertex::new(1.0, 0.2, 0.0));
+        assert_eq!(snapped.vertices()[2].z, 0.0);
+        assert_eq!(a.snap_coords(0.5).vertices(), a.vertices());
+    }
+
+    #[test]
+    fn segment_hit_points() {
+        let t = triangle![[0, 0, 0], [1, 0, 0], [0, 1, 0]];
+        let edge = |p: [f64; 3], q: [f64; 3]| Edge::new(p.into(), q.into());
+
+        let hit = t
+            .segment_hit(&edge([0.25, 0.5, -1.0], [0.25, 0.0, 3.0]))
+            .unwrap();
+        assert!(
+            hit.abs_diff_eq(&Vertex::new(0.25, 0.375, 0.0), 1e-12),
+            "{:?}",
+            hit
+        );
+
+        // Short of the plane, beside the triangle, and lying in its plane
+        assert_eq!(t.segment_hit(&edge([0.2, 0.2, 1.0], [0.2, 0.2, 0.5])), None);
+        assert_eq!(
+            t.segment_hit(&edge([2.0, 2.0, -1.0], [2.0, 2.0, 1.0])),
+            None
+        );
+        assert_eq!(
+            t.segment_hit(&edge([-1.0, 0.3, 0.0], [2.0, 0.3, 0.0])),
+            None
+        );
+    }
+
+    #[test]
+    fn coplanarity_residual_distances() {
+        let plane = triangle![[0, 0, 0], [2, 0, 0], [0, 2, 0]];
+
+        let other = triangle![[5, 5, 0.25], [-3, 1, -0.75], [1, 1, 0.5]];
+        assert_eq!(plane.coplanarity_residual(&other), 0.75);
+        assert_eq!(plane.coplanarity_residual(&plane), 0.0);
+
+        // Measured along the unit normal of the plane x + y = 2
+        let slanted = triangle![[2, 0, 0], [0, 2, 0], [0, 2, 1]];
+        let r = slanted.coplanarity_residual(&triangle![[0, 0, 0], [1, 1, 0], [2, 2, 0]]);
+        assert!((r - 2f64.sqrt()).abs() < 1e-12);
+
+        assert!(triangle![[0, 0, 0], [1, 1, 1], [2, 2, 2]]
+            .coplanarity_residual(&plane)
+            .is_nan());
+    }
+
+    #[test]
+    fn array_round_trip() {
+        let coords = [1.5, -0.0, 1e300];
+        let v = Vertex::from_array(coords);
+
+        assert_eq!(v, Vertex::new(1.5, -0.0, 1e300));
+        assert_eq!(v.to_array(), coords);
+        assert_eq!(Vertex::from(coords), v);
+        assert_eq!(<[f64; 3]>::from(v), coords);
+
+        let back: [f64; 3] = Vertex::from(coords).into();
+        assert_eq!(back.map(f64::to_bits), coords.map(f64::to_bits));
+    }
+
+    #[test]
+    fn obb_contains_vertices() {
+        for t in [
+            triangle![[0, 0, 0], [4, 0, 0], [0, 3, 0]],
+            triangle![[1, 2, 3], [-4, 0.5, 2], [3, -1, -7]],
+            triangle![[0, 0, 0], [10, 0.1, 0.1], [5, 0.3, -0.2]],
+        ] {
+            let obb = t.obb();
+
+            for (i, a) in obb.axes.iter().enumerate() {
+                assert!((a.length() - 1.0).abs() < 1e-12);
+                for b in &obb.axes[(i + 1)..] {
+                    assert!(a.dot_product(b).abs() < 1e-12);
+                }
+            }
+            assert_eq!(obb.half_extents[2], 0.0);
+
+            for v in t.vertices() {
+                let d = v.subtract(&obb.center);
+                for (axis, half) in obb.axes.iter().zip(obb.half_extents) {
+                    assert!(
+                        d.dot_product(axis).abs() <= half + 1e-9,
+                        "{:?} {:?}",
+                        v,
+                        obb
+                    );
+                }
+            }
+        }
+
+        // A right triangle's box has its legs as sides
+        let obb = triangle![[0, 0, 0], [4, 0, 0], [0, 3, 0]].obb();
+        assert!((obb.half_extents[0] * obb.half_extents[1] - 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn signed_area_2d_by_winding() {
+        let ccw = triangle![[0, 0, 7], [2, 0, 1], [0, 3, -2]];
+        let cw = triangle![[0, 0, 7], [0, 3, -2], [2, 0, 1]];
+
+        assert_eq!(ccw.signed_area_2d(Axis::Z), 3.0);
+        assert_eq!(cw.signed_area_2d(Axis::Z), -3.0);
+
+        // Dropping x keeps (y, z), so a counterclockwise turn in y then z
+        let yz = triangle![[5, 0, 0], [5, 1, 0], [5, 0, 1]];
+        assert_eq!(yz.signed_area_2d(Axis::X), 0.5);
+        assert_eq!(yz.signed_area_2d(Axis::Z), 0.0);
+    }
+
+    #[test]
+    fn capsules() {
+        let t = triangle![[0, 0, 0], [2, 0, 0], [0, 2, 0]];
+        let v = |x: f64, y: f64, z: f64| Vertex::new(x, y, z);
+
+        // Straight through the face, even with no radius
+        assert!(t.intersects_capsule(&v(0.5, 0.5, -1.0), &v(0.5, 0.5, 1.0), 0.0));
+
+        // Above the face, past an edge and past a vertex, 0.05 away each time
+        for (a, b) in [
+            (v(0.2, 0.5, 0.05), v(1.0, 0.5, 0.05)),
+            (v(1.0, -0.05, -1.0), v(1.0, -0.05, 1.0)),
+            (v(-0.05, 0.0, -1.0), v(-0.05, 0.0, 1.0)),
+        ] {
+            assert!(t.intersects_capsule(&a, &b, 0.1), "{:?} {:?}", a, b);
+            assert!(!t.intersects_capsule(&a, &b, 0.04), "{:?} {:?}", a, b);
+        }
+
+        // An end cap reaching down to the face
+        assert!(t.intersects_capsule(&v(0.5, 0.5, 0.3), &v(0.5, 0.5, 3.0), 0.3));
+        assert!(!t.intersects_capsule(&v(0.5, 0.5, 0.3), &v(0.5, 0.5, 3.0), 0.29));
+
+        assert!(!t.intersects_capsule(&v(5.0, 5.0, 5.0), &v(6.0, 6.0, 6.0), 1.0));
+    }
+
+    #[test]
+    fn triangle_macro_matches_new() {
+        let explicit = Triangle::new(
+            Vertex::new(0.0, 0.0, 0.0),
+            Vertex::new(1.0, 0.0, 0.0),
+            Vertex::new(0.0, 0.5, -2.0),
+        );
+        let n = 2;
+
+        assert_eq!(
+            triangle![[0, 0, 0], [1, 0, 0], [0, 0.5, -2]].vertices(),
+            explicit.vertices()
+        );
+        assert_eq!(
+            triangle![[0.0, 0, 0], [n - 1, 0, 0], [0, 1.0 / 2.0, -n],].vertices(),
+            explicit.vertices()
+        );
+    }
+}
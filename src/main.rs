@@ -1,181 +1,283 @@
-use std::io;
+use std::env;
+use std::io::{self, BufRead};
+use std::process;
 
-#[derive(Clone, Copy)]
-struct Vertex {
-    x: f64,
-    y: f64,
-    z: f64,
+use triangle_intersect::{Triangle, Vertex};
+
+/*
+ * Parse a line of whitespace separated coordinates.
+ *
+ * Each coordinate follows Rust's f64 grammar:
+ *
+ *   [+|-] (digits [. [digits]] | . digits) [(e|E) [+|-] digits]
+ *
+ * so integers (1), leading-dot decimals (.5), signed values (+2, -0.0) and
+ * scientific notation (1e3) are all accepted. Non-finite values (inf, nan)
+ * are rejected, as is the whole line if any token fails to parse, rather
+ * than silently dropping that token.
+ */
+fn parse_floats(line: &str) -> Option<Vec<f64>> {
+    line.split_ascii_whitespace()
+        .map(|token| token.parse::<f64>().ok().filter(|c| c.is_finite()))
+        .collect()
 }
 
-impl Vertex {
-    fn new(x: f64, y: f64, z: f64) -> Vertex {
-        Vertex { x, y, z }
-    }
+/* Parse a line of exactly N coordinates */
+fn parse_coords<const N: usize>(line: &str) -> Option<[f64; N]> {
+    parse_floats(line)?.try_into().ok()
+}
 
-    fn subtract(&self, v: &Vertex) -> Vertex {
-        Vertex {
-            x: self.x - v.x,
-            y: self.y - v.y,
-            z: self.z - v.z,
-        }
-    }
+fn parse_triangle(line: &str) -> Option<[Vertex; 3]> {
+    let c: [f64; 9] = parse_coords(line)?;
+    Some([
+        Vertex::new(c[0], c[1], c[2]),
+        Vertex::new(c[3], c[4], c[5]),
+        Vertex::new(c[6], c[7], c[8]),
+    ])
+}
 
-    fn cross_product(&self, v: &Vertex) -> Vertex {
-        // Yeah, I am using a Vertex as a 3-by-1 matrix...
-        Vertex {
-            x: (self.y * v.z) - (self.z * v.y),
-            y: -((self.x * v.z) - (self.z * v.x)),
-            z: (self.x * v.y) - (self.y * v.x),
-        }
-    }
+fn parse_pair(line: &str) -> Option<[[Vertex; 3]; 2]> {
+    let c: [f64; 18] = parse_coords(line)?;
+    let v = |i: usize| Vertex::new(c[3 * i], c[3 * i + 1], c[3 * i + 2]);
+    Some([[v(0), v(1), v(2)], [v(3), v(4), v(5)]])
+}
 
-    fn dot_product(&self, v: &Vertex) -> f64 {
-        (self.x * v.x) + (self.y * v.y) + (self.z * v.z)
+fn answer(intersect: bool) -> &'static str {
+    if intersect {
+        "yes"
+    } else {
+        "no"
     }
 }
 
-struct Edge {
-    vertices: [Vertex; 2],
+/*
+ * For CI harnesses: TRI_A and TRI_B each hold nine coordinates. Returns None
+ * when neither is set.
+ */
+fn env_triangles() -> Result<Option<[[Vertex; 3]; 2]>, String> {
+    let (a, b) = match (env::var("TRI_A"), env::var("TRI_B")) {
+        (Err(_), Err(_)) => return Ok(None),
+        (Ok(a), Ok(b)) => (a, b),
+        _ => return Err("TRI_A and TRI_B must be set together".to_string()),
+    };
+
+    let parse = |name: &str, value: &str| {
+        parse_triangle(value).ok_or(format!("{} must hold nine coordinates", name))
+    };
+
+    Ok(Some([parse("TRI_A", &a)?, parse("TRI_B", &b)?]))
 }
 
-impl Edge {
-    fn new(u: Vertex, v: Vertex) -> Edge {
-        Edge { vertices: [u, v] }
-    }
+struct Options {
+    // Multiplies every input coordinate, for working in other units. Any
+    // finite non-zero factor leaves the answer unchanged.
+    scale: f64,
+    // One pair of triangles (18 coordinates) per line until EOF
+    labeled: bool,
+    // Report only through the exit status, printing nothing
+    exit_code: bool,
 }
 
-struct Triangle {
-    vertices: [Vertex; 3],
-    edges: [Edge; 3],
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        scale: 1.0,
+        labeled: false,
+        exit_code: false,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--scale" => {
+                let factor = args.next().ok_or("--scale requires a FACTOR")?;
+                options.scale = match factor.parse::<f64>() {
+                    // Zero would collapse every triangle to a point
+                    Ok(factor) if factor.is_finite() && factor != 0.0 => factor,
+                    _ => return Err(format!("Invalid --scale FACTOR: {}", factor)),
+                };
+            }
+            "--labeled" => options.labeled = true,
+            "--exit-code" => options.exit_code = true,
+            _ => return Err(format!("Unknown argument: {}", arg)),
+        }
+    }
+
+    if options.labeled && options.exit_code {
+        return Err("--labeled and --exit-code can't be combined".to_string());
+    }
+
+    Ok(options)
 }
 
 /*
- * V = (1/6) * det(a - d, b - d, c - d)
- * V = (1/6) * (([a - d] X [b - d]) o [c - d])
- *
- * Link: https://en.wikipedia.org/wiki/Tetrahedron#Volume
+ * Print "pair N: yes" or "pair N: no" for each non-blank line, counting from
+ * 0. Lines that aren't 18 coordinates still use up an index. Input that
+ * can't be read, e.g. invalid UTF-8, ends the run with an error.
  */
-fn tetrahedran_signed_volume(a: &Vertex, b: &Vertex, c: &Vertex, d: &Vertex) -> f64 {
-    (a.subtract(d)
-        .cross_product(&b.subtract(d))
-        .dot_product(&c.subtract(d)))
-        / 6.0
-}
+fn run_labeled(options: &Options) {
+    let stdin = io::stdin();
+    let mut idx = 0;
 
-impl Triangle {
-    fn new(u: Vertex, v: Vertex, w: Vertex) -> Triangle {
-        Triangle {
-            vertices: [u, v, w],
-            edges: [Edge::new(u, v), Edge::new(v, w), Edge::new(w, u)],
+    for line in stdin.lock().lines() {
+        let line = line.unwrap_or_else(|e| fail(options, &format!("Failed to read line: {}", e)));
+        if line.trim().is_empty() {
+            continue;
         }
-    }
 
-    /* Link: https://stackoverflow.com/a/42752998 */
-    fn edge_intersect(&self, e: &Edge) -> bool {
-        // Use each point in the provided edge
-        let mut sv_e_arr: [f64; 2] = [0.0; 2];
-        for i in 0..2 {
-            sv_e_arr[i] = tetrahedran_signed_volume(
-                &self.vertices[0],
-                &self.vertices[1],
-                &self.vertices[2],
-                &e.vertices[i],
-            );
+        match parse_pair(&line) {
+            Some(pair) => println!(
+                "pair {}: {}",
+                idx,
+                answer(intersect_pair(pair, options.scale))
+            ),
+            None => eprintln!("pair {}: expected 18 coordinates", idx),
         }
+        idx += 1;
+    }
+}
+
+/*
+ * Read the 18 coordinates of two triangles, accumulated across lines so a
+ * vertex, a whole triangle or anything in between can go on one line. Bad
+ * lines are reported on stderr, dropped and re-prompted for, unless
+ * --exit-code is given, in which case they are an error.
+ */
+fn read_interactive(options: &Options) -> Result<[[Vertex; 3]; 2], String> {
+    let mut coords: Vec<f64> = Vec::with_capacity(18);
 
-        // Use each of the triangles edges
-        let mut sv_t_arr: [f64; 3] = [0.0; 3];
-        for i in 0..3 {
-            sv_t_arr[i] = tetrahedran_signed_volume(
-                &self.edges[i].vertices[0],
-                &self.edges[i].vertices[1],
-                &e.vertices[0],
-                &e.vertices[1],
+    while coords.len() < 18 {
+        if !options.exit_code {
+            println!(
+                "Please input floating point values (ex. 0.0 0.0 0.0) for vertex {} of triangle {}.",
+                (coords.len() / 3) % 3 + 1,
+                coords.len() / 9 + 1
             );
         }
 
-        sv_e_arr[0].signum() != sv_e_arr[1].signum()
-            && sv_t_arr[0].signum() == sv_t_arr[1].signum()
-            && sv_t_arr[1].signum() == sv_t_arr[2].signum()
-            && sv_t_arr[2].signum() == sv_t_arr[0].signum()
-    }
+        let mut line = String::new();
 
-    fn intersect(&self, t: &Triangle) -> bool {
-        // Check if any edge in t intersects self
-        for edge in &t.edges {
-            if self.edge_intersect(edge) {
-                return true;
-            }
+        let read = io::stdin()
+            .read_line(&mut line)
+            .map_err(|e| format!("Failed to read line: {}", e))?;
+        if read == 0 {
+            return Err("Unexpected end of input".to_string());
         }
 
-        // Check if any edge in self intersects t
-        for edge in &self.edges {
-            if t.edge_intersect(edge) {
-                return true;
+        let error = match parse_floats(&line) {
+            Some(floats) if coords.len() + floats.len() <= 18 => {
+                coords.extend(floats);
+                continue;
             }
+            Some(floats) => format!(
+                "Too many values: {} given, {} still needed",
+                floats.len(),
+                18 - coords.len()
+            ),
+            None => format!("Invalid input: {}", line.trim()),
+        };
+
+        if options.exit_code {
+            return Err(error);
         }
+        eprintln!("{}, ignoring the line", error);
+    }
+
+    let v = |i: usize| Vertex::new(coords[3 * i], coords[3 * i + 1], coords[3 * i + 2]);
+    Ok([[v(0), v(1), v(2)], [v(3), v(4), v(5)]])
+}
+
+fn intersect_pair(pair: [[Vertex; 3]; 2], scale: f64) -> bool {
+    let [t1, t2] = pair.map(|t| Triangle::from(t.map(|v| v.scale(scale))));
+    t1.intersect(&t2)
+}
+
+/* With --exit-code: 0 when the triangles intersect, 1 when they don't */
+fn exit_with(intersect: bool) -> ! {
+    process::exit(if intersect { 0 } else { 1 })
+}
 
-        false
+fn fail(options: &Options, msg: &str) -> ! {
+    if !options.exit_code {
+        eprintln!("{}", msg);
     }
+
+    process::exit(2)
 }
 
 fn main() {
-    let mut triangle_idx = 1;
-    let mut vertex_idx = 1;
+    let args: Vec<String> = env::args().skip(1).collect();
 
-    let mut vertices = [Vertex::new(0.0, 0.0, 0.0); 6];
+    let options = match parse_args(args.iter().cloned()) {
+        Ok(options) => options,
+        Err(msg) => {
+            // Bad arguments are still reported through the status alone
+            if !args.iter().any(|arg| arg == "--exit-code") {
+                eprintln!("{}", msg);
+                eprintln!("Usage: triangle-intersect [--scale FACTOR] [--labeled | --exit-code]");
+            }
+            process::exit(2);
+        }
+    };
 
-    while triangle_idx <= 2 {
-        println!(
-            "Please input floating point values (ex. 0.0 0.0 0.0) for vertex {} of triangle {}.",
-            vertex_idx, triangle_idx
-        );
+    match env_triangles() {
+        Ok(Some(pair)) => {
+            let intersect = intersect_pair(pair, options.scale);
+            if options.exit_code {
+                exit_with(intersect);
+            }
 
-        let mut coords = String::new();
+            println!("{}", answer(intersect));
+            return;
+        }
+        Err(msg) => fail(&options, &msg),
+        Ok(None) => (),
+    }
 
-        io::stdin()
-            .read_line(&mut coords)
-            .expect("Failed to read line");
+    if options.labeled {
+        run_labeled(&options);
+        return;
+    }
 
-        let mut coord_vec: Vec<f64> = Vec::new();
+    let pair = match read_interactive(&options) {
+        Ok(pair) => pair,
+        Err(msg) => fail(&options, &msg),
+    };
 
-        for coord in coords.split_ascii_whitespace() {
-            let coord: f64 = match coord.trim().parse() {
-                Ok(num) => num,
-                Err(_) => continue,
-            };
+    let intersect = intersect_pair(pair, options.scale);
+    if options.exit_code {
+        exit_with(intersect);
+    }
 
-            coord_vec.push(coord);
-        }
+    println!("Do the two triangles intersect?: {}", answer(intersect))
+}
 
-        if coord_vec.len() != 3 {
-            continue;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        vertices[((triangle_idx - 1) * 3) + (vertex_idx - 1)] =
-            Vertex::new(coord_vec[0], coord_vec[1], coord_vec[2]);
+    #[test]
+    fn parses_numeric_forms() {
+        assert_eq!(parse_floats("1"), Some(vec![1.0]));
+        assert_eq!(parse_floats("1e3"), Some(vec![1000.0]));
+        assert_eq!(parse_floats(".5"), Some(vec![0.5]));
+        assert_eq!(parse_floats("+2"), Some(vec![2.0]));
 
-        vertex_idx += 1;
+        let neg_zero = parse_floats("-0.0").unwrap();
+        assert_eq!(neg_zero, vec![0.0]);
+        assert!(neg_zero[0].is_sign_negative());
 
-        if vertex_idx > 3 {
-            triangle_idx += 1;
-            vertex_idx = 1;
-        }
+        assert_eq!(parse_coords("1 1e3 .5"), Some([1.0, 1000.0, 0.5]));
+        assert_eq!(parse_coords("  -0.0\t+2  1E-1 \n"), Some([-0.0, 2.0, 0.1]));
     }
 
-    let t1 = Triangle::new(
-        vertices[0],
-        vertices[1],
-        vertices[2],
-    );
-    let t2 = Triangle::new(
-        vertices[3],
-        vertices[4],
-        vertices[5],
-    );
-
-    println!(
-        "Do the two triangles intersect?: {}",
-        if t1.intersect(&t2) { "yes" } else { "no" }
-    )
+    #[test]
+    fn rejects_bad_lines_whole() {
+        assert_eq!(parse_floats("1 x 2"), None);
+        assert_eq!(parse_floats("1 inf"), None);
+        assert_eq!(parse_floats("nan"), None);
+        assert_eq!(parse_floats("1,2"), None);
+
+        assert_eq!(parse_coords::<3>("1 2"), None);
+        assert_eq!(parse_coords::<3>("1 2 3 4"), None);
+        assert_eq!(parse_coords::<3>("1 2 e"), None);
+    }
 }
@@ -0,0 +1,180 @@
+use crate::bvh::Bvh;
+use crate::{Edge, Intersection, Triangle, Vertex};
+
+/// Indexed triangle storage: shared vertices plus faces indexing into them.
+pub struct Mesh {
+    pub vertices: Vec<Vertex>,
+    pub faces: Vec<[usize; 3]>,
+    /// Per-vertex RGB colors, either empty or one entry per vertex. They
+    /// are carried along for reporting and never affect the geometry.
+    pub colors: Vec<Option<[u8; 3]>>,
+}
+
+impl Mesh {
+    pub fn new(vertices: Vec<Vertex>, faces: Vec<[usize; 3]>) -> Mesh {
+        Mesh {
+            vertices,
+            faces,
+            colors: Vec::new(),
+        }
+    }
+
+    /// Color of vertex `i`, if the mesh has one for it.
+    pub fn color(&self, i: usize) -> Option<[u8; 3]> {
+        self.colors.get(i).copied().flatten()
+    }
+
+    /// Materialize face `i` as a `Triangle`.
+    pub fn triangle(&self, i: usize) -> Triangle {
+        let [u, v, w] = self.faces[i];
+        Triangle::new(self.vertices[u], self.vertices[v], self.vertices[w])
+    }
+
+    pub fn triangles(&self) -> Triangles<'_> {
+        Triangles {
+            mesh: self,
+            next: 0,
+        }
+    }
+}
+
+/// Iterator materializing each face of a `Mesh` as a `Triangle`.
+pub struct Triangles<'a> {
+    mesh: &'a Mesh,
+    next: usize,
+}
+
+impl Iterator for Triangles<'_> {
+    type Item = Triangle;
+
+    fn next(&mut self) -> Option<Triangle> {
+        if self.next == self.mesh.faces.len() {
+            return None;
+        }
+
+        self.next += 1;
+        Some(self.mesh.triangle(self.next - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.mesh.faces.len() - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Triangles<'_> {}
+
+impl<'a> IntoIterator for &'a Mesh {
+    type Item = Triangle;
+    type IntoIter = Triangles<'a>;
+
+    fn into_iter(self) -> Triangles<'a> {
+        self.triangles()
+    }
+}
+
+/// Every segment along which a face of `a` passes through a face of `b`, as
+/// in `Intersection::Crossing`, ordered by face of `a`, then face of `b`.
+/// Together they trace the intersection curve of the two meshes. Faces that
+/// only touch, or are coplanar, contribute no segments.
+pub fn mesh_mesh_intersection_segments(a: &Mesh, b: &Mesh) -> Vec<Edge> {
+    let b_tris: Vec<Triangle> = b.triangles().collect();
+
+    let mut bvh = Bvh::default();
+    for (i, t) in b_tris.iter().enumerate() {
+        bvh.insert(t.aabb(), i);
+    }
+
+    let mut segments = Vec::new();
+
+    for t in a {
+        let mut candidates = bvh.query(&t.aabb());
+        candidates.sort_unstable();
+
+        segments.extend(candidates.into_iter().filter_map(|i| {
+            match t.intersection_detail(&b_tris[i]) {
+                Some(Intersection::Crossing(segment)) => Some(segment),
+                _ => None,
+            }
+        }));
+    }
+
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /* Corner at `o`, legs of length `size` along the axes */
+    fn tetrahedron(o: Vertex, size: f64) -> Mesh {
+        let corner = |x: f64, y: f64, z: f64| o.add(&Vertex::new(x, y, z).scale(size));
+        Mesh::new(
+            vec![
+                corner(0.0, 0.0, 0.0),
+                corner(1.0, 0.0, 0.0),
+                corner(0.0, 1.0, 0.0),
+                corner(0.0, 0.0, 1.0),
+            ],
+            vec![[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]],
+        )
+    }
+
+    #[test]
+    fn interpenetrating_tetrahedra() {
+        // b's corner pokes through a's slanted face x + y + z = 1, cut by
+        // b's three axis-aligned faces
+        let a = tetrahedron(Vertex::new(0.0, 0.0, 0.0), 1.0);
+        let b = tetrahedron(Vertex::new(0.3, 0.3, 0.3), 1.0);
+
+        let segments = mesh_mesh_intersection_segments(&a, &b);
+        assert_eq!(segments.len(), 3);
+        for v in segments.iter().flat_map(|e| e.vertices) {
+            assert!((v.x + v.y + v.z - 1.0).abs() < 1e-12, "{:?}", v);
+        }
+
+        assert_eq!(mesh_mesh_intersection_segments(&b, &a).len(), 3);
+    }
+
+    #[test]
+    fn touching_meshes_have_no_curve() {
+        // Mirrored across y = 0: a shared face and edges, but no crossing
+        let a = tetrahedron(Vertex::new(0.0, 0.0, 0.0), 1.0);
+        let mut b = tetrahedron(Vertex::new(0.0, 0.0, 0.0), 1.0);
+        b.vertices[2] = Vertex::new(0.0, -1.0, 0.0);
+
+        assert!(mesh_mesh_intersection_segments(&a, &b).is_empty());
+
+        // Only a vertex in common
+        let c = tetrahedron(Vertex::new(1.0, 0.0, 0.0), 1.0);
+        assert!(mesh_mesh_intersection_segments(&a, &c).is_empty());
+
+        let empty = Mesh::new(Vec::new(), Vec::new());
+        assert!(mesh_mesh_intersection_segments(&a, &empty).is_empty());
+        assert!(mesh_mesh_intersection_segments(&empty, &a).is_empty());
+    }
+
+    #[test]
+    fn iterates_faces_as_triangles() {
+        let mesh = tetrahedron(Vertex::new(1.0, 2.0, 3.0), 2.0);
+
+        let tris: Vec<Triangle> = (&mesh).into_iter().collect();
+        assert_eq!(tris.len(), 4);
+        assert_eq!(
+            tris[0].vertices(),
+            &[
+                Vertex::new(1.0, 2.0, 3.0),
+                Vertex::new(1.0, 4.0, 3.0),
+                Vertex::new(3.0, 2.0, 3.0),
+            ]
+        );
+
+        let mut count = 0;
+        for t in &mesh {
+            assert_eq!(t.vertices(), mesh.triangle(count).vertices());
+            count += 1;
+        }
+        assert_eq!(count, mesh.faces.len());
+        assert_eq!(mesh.triangles().len(), 4);
+    }
+}
@@ -0,0 +1,123 @@
+/*
+ * 2D predicates used once a problem has been projected onto a coordinate
+ * plane. Boundaries count as inside / intersecting throughout.
+ */
+
+use crate::{Axis, Vertex};
+
+pub(crate) type Point2 = (f64, f64);
+
+/* The axis of the largest absolute normal component */
+pub(crate) fn dominant_axis(n: &Vertex) -> Axis {
+    let (ax, ay, az) = (n.x.abs(), n.y.abs(), n.z.abs());
+
+    if ax >= ay && ax >= az {
+        Axis::X
+    } else if ay >= az {
+        Axis::Y
+    } else {
+        Axis::Z
+    }
+}
+
+pub(crate) fn project(v: &Vertex, axis: Axis) -> Point2 {
+    match axis {
+        Axis::X => (v.y, v.z),
+        Axis::Y => (v.z, v.x),
+        Axis::Z => (v.x, v.y),
+    }
+}
+
+/* Twice the signed area of (a, b, c), positive when counterclockwise */
+pub(crate) fn orient(a: Point2, b: Point2, c: Point2) -> f64 {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+pub(crate) fn point_in_triangle(p: Point2, t: &[Point2; 3]) -> bool {
+    // Every orientation below vanishes for a zero-area triangle, so only its
+    // sides can contain p
+    if orient(t[0], t[1], t[2]) == 0.0 {
+        return (0..3).any(|i| {
+            let (a, b) = (t[i], t[(i + 1) % 3]);
+            orient(a, b, p) == 0.0 && on_segment(a, b, p)
+        });
+    }
+
+    let d = [
+        orient(t[0], t[1], p),
+        orient(t[1], t[2], p),
+        orient(t[2], t[0], p),
+    ];
+
+    d.iter().all(|&d| d >= 0.0) || d.iter().all(|&d| d <= 0.0)
+}
+
+/* Is q, known to be collinear with p1 and p2, within their bounding box? */
+fn on_segment(p1: Point2, p2: Point2, q: Point2) -> bool {
+    q.0 >= p1.0.min(p2.0) && q.0 <= p1.0.max(p2.0) && q.1 >= p1.1.min(p2.1) && q.1 <= p1.1.max(p2.1)
+}
+
+/* Link: https://en.wikipedia.org/wiki/Line_segment_intersection */
+pub(crate) fn segments_intersect(p1: Point2, p2: Point2, q1: Point2, q2: Point2) -> bool {
+    let d1 = orient(q1, q2, p1);
+    let d2 = orient(q1, q2, p2);
+    let d3 = orient(p1, p2, q1);
+    let d4 = orient(p1, p2, q2);
+
+    if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0))
+        && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+    {
+        return true;
+    }
+
+    (d1 == 0.0 && on_segment(q1, q2, p1))
+        || (d2 == 0.0 && on_segment(q1, q2, p2))
+        || (d3 == 0.0 && on_segment(p1, p2, q1))
+        || (d4 == 0.0 && on_segment(p1, p2, q2))
+}
+
+pub(crate) fn segment_intersects_triangle(p1: Point2, p2: Point2, t: &[Point2; 3]) -> bool {
+    point_in_triangle(p1, t) || (0..3).any(|i| segments_intersect(p1, p2, t[i], t[(i + 1) % 3]))
+}
+
+/* Part of a convex polygon inside a non-degenerate triangle */
+pub(crate) fn clip_to_triangle(subject: &[Point2], t: &[Point2; 3]) -> Vec<Point2> {
+    let t = if orient(t[0], t[1], t[2]) < 0.0 {
+        [t[0], t[2], t[1]]
+    } else {
+        *t
+    };
+
+    let mut poly = subject.to_vec();
+    for i in 0..3 {
+        let (a, b) = (t[i], t[(i + 1) % 3]);
+        let input = std::mem::take(&mut poly);
+
+        for (j, &p) in input.iter().enumerate() {
+            let q = input[(j + 1) % input.len()];
+            let (dp, dq) = (orient(a, b, p), orient(a, b, q));
+
+            if dp >= 0.0 {
+                poly.push(p);
+            }
+            if (dp >= 0.0) != (dq >= 0.0) {
+                let s = dp / (dp - dq);
+                poly.push((p.0 + (q.0 - p.0) * s, p.1 + (q.1 - p.1) * s));
+            }
+        }
+    }
+
+    poly
+}
+
+/* Unsigned area of a simple polygon (shoelace formula) */
+pub(crate) fn polygon_area(poly: &[Point2]) -> f64 {
+    let twice: f64 = (0..poly.len())
+        .map(|i| {
+            let (p, q) = (poly[i], poly[(i + 1) % poly.len()]);
+            p.0 * q.1 - q.0 * p.1
+        })
+        .sum();
+
+    twice.abs() / 2.0
+}
@@ -0,0 +1,213 @@
+/*
+ * Minimal ASCII PLY reader: vertex positions, optional red/green/blue vertex
+ * colors and polygon faces, which are fanned into triangles. Other elements
+ * and properties are skipped.
+ *
+ * Link: https://paulbourke.net/dataformats/ply/
+ */
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::{Mesh, Vertex};
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<String>,
+}
+
+fn invalid(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+fn parse<T: std::str::FromStr>(token: Option<&str>, what: &str) -> io::Result<T> {
+    token
+        .and_then(|t| t.parse().ok())
+        .ok_or_else(|| invalid(format!("PLY: bad {}", what)))
+}
+
+/// Read an ASCII PLY file into a `Mesh`, keeping vertex colors when the file
+/// has `red`, `green` and `blue` vertex properties.
+pub fn read_ply(path: &Path) -> io::Result<Mesh> {
+    let text = fs::read_to_string(path)?;
+    let mut lines = text.lines();
+
+    if lines.next().map(str::trim) != Some("ply") {
+        return Err(invalid("PLY: missing magic number"));
+    }
+
+    let mut elements: Vec<Element> = Vec::new();
+    loop {
+        let line = lines
+            .next()
+            .ok_or_else(|| invalid("PLY: missing end_header"))?;
+        let mut tokens = line.split_ascii_whitespace();
+
+        match tokens.next() {
+            Some("format") if tokens.next() != Some("ascii") => {
+                return Err(invalid("PLY: only the ascii format is supported"));
+            }
+            Some("element") => {
+                let name = tokens.next().unwrap_or_default().to_string();
+                let count = parse(tokens.next(), "element count")?;
+                elements.push(Element {
+                    name,
+                    count,
+                    properties: Vec::new(),
+                });
+            }
+            Some("property") => {
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| invalid("PLY: property before element"))?;
+                let name = tokens.last().unwrap_or_default().to_string();
+                element.properties.push(name);
+            }
+            Some("end_header") => break,
+            _ => (),
+        }
+    }
+
+    let mut vertices = Vec::new();
+    let mut colors = Vec::new();
+    let mut faces = Vec::new();
+
+    for element in &elements {
+        let find = |name: &str| element.properties.iter().position(|p| p == name);
+        let xyz = [find("x"), find("y"), find("z")];
+        let rgb = [find("red"), find("green"), find("blue")];
+
+        for _ in 0..element.count {
+            let line = lines.next().ok_or_else(|| invalid("PLY: truncated body"))?;
+            let tokens: Vec<&str> = line.split_ascii_whitespace().collect();
+
+            match element.name.as_str() {
+                "vertex" => {
+                    let mut c = [0.0; 3];
+                    for (c, i) in c.iter_mut().zip(xyz) {
+                        let i = i.ok_or_else(|| invalid("PLY: vertex without x, y and z"))?;
+                        *c = parse(tokens.get(i).copied(), "vertex coordinate")?;
+                    }
+                    vertices.push(Vertex::new(c[0], c[1], c[2]));
+
+                    if let [Some(r), Some(g), Some(b)] = rgb {
+                        colors.push(Some([
+                            parse(tokens.get(r).copied(), "vertex color")?,
+                            parse(tokens.get(g).copied(), "vertex color")?,
+                            parse(tokens.get(b).copied(), "vertex color")?,
+                        ]));
+                    }
+                }
+                "face" => {
+                    let n: usize = parse(tokens.first().copied(), "face size")?;
+                    let mut idx = Vec::with_capacity(n);
+                    for i in 1..=n {
+                        idx.push(parse::<usize>(tokens.get(i).copied(), "face index")?);
+                    }
+
+                    for i in 1..n.saturating_sub(1) {
+                        faces.push([idx[0], idx[i], idx[i + 1]]);
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    if faces.iter().flatten().any(|&i| i >= vertices.len()) {
+        return Err(invalid("PLY: face index out of range"));
+    }
+
+    let mut mesh = Mesh::new(vertices, faces);
+    mesh.colors = colors;
+    Ok(mesh)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /* Write `text` to a path in the temp directory that no other test uses */
+    fn fixture(name: &str, text: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "triangle-intersect-{}-{}.ply",
+            std::process::id(),
+            name
+        ));
+        fs::write(&path, text).unwrap();
+        path
+    }
+
+    #[test]
+    fn colors_survive_to_the_mesh() {
+        let path = fixture(
+            "colored",
+            "ply\n\
+             format ascii 1.0\n\
+             comment a colored unit square\n\
+             element vertex 4\n\
+             property float x\n\
+             property float y\n\
+             property float z\n\
+             property uchar red\n\
+             property uchar green\n\
+             property uchar blue\n\
+             element face 1\n\
+             property list uchar int vertex_indices\n\
+             end_header\n\
+             0 0 0 255 0 0\n\
+             1 0 0 0 255 0\n\
+             1 1 0 0 0 255\n\
+             0 1 0 10 20 30\n\
+             4 0 1 2 3\n",
+        );
+        let mesh = read_ply(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(mesh.vertices[2], Vertex::new(1.0, 1.0, 0.0));
+        assert_eq!(mesh.faces, vec![[0, 1, 2], [0, 2, 3]]);
+        assert_eq!(
+            mesh.colors,
+            vec![
+                Some([255, 0, 0]),
+                Some([0, 255, 0]),
+                Some([0, 0, 255]),
+                Some([10, 20, 30]),
+            ]
+        );
+        assert_eq!(mesh.color(3), Some([10, 20, 30]));
+        assert_eq!(mesh.color(4), None);
+    }
+
+    #[test]
+    fn without_colors() {
+        let path = fixture(
+            "plain",
+            "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\n\
+             property float z\nelement face 1\nproperty list uchar int vertex_indices\n\
+             end_header\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n",
+        );
+        let mesh = read_ply(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(mesh.faces, vec![[0, 1, 2]]);
+        assert!(mesh.colors.is_empty());
+        assert_eq!(mesh.color(0), None);
+    }
+
+    #[test]
+    fn only_ascii() {
+        let path = fixture(
+            "binary",
+            "ply\nformat binary_little_endian 1.0\nelement vertex 0\nend_header\n",
+        );
+        let err = read_ply(&path).err().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("ascii"), "{}", err);
+    }
+}
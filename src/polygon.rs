@@ -0,0 +1,133 @@
+use crate::{fan, Triangle, Vertex};
+
+/// Four roughly coplanar vertices in order around the face.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quad {
+    pub vertices: [Vertex; 4],
+}
+
+impl Quad {
+    pub fn new(a: Vertex, b: Vertex, c: Vertex, d: Vertex) -> Quad {
+        Quad {
+            vertices: [a, b, c, d],
+        }
+    }
+
+    /// Split along the a-c diagonal into (a, b, c) and (a, c, d).
+    pub fn triangulate(&self) -> [Triangle; 2] {
+        let [a, b, c, d] = self.vertices;
+        [Triangle::new(a, b, c), Triangle::new(a, c, d)]
+    }
+
+    /// Whether any triangle of one quad intersects any triangle of the other.
+    pub fn intersect(&self, other: &Quad) -> bool {
+        let theirs = other.triangulate();
+
+        self.triangulate()
+            .iter()
+            .any(|t| theirs.iter().any(|o| t.intersect(o)))
+    }
+}
+
+/// Coplanar vertices in order around a convex polygon.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConvexPolygon {
+    pub vertices: Vec<Vertex>,
+}
+
+impl ConvexPolygon {
+    pub fn new(vertices: Vec<Vertex>) -> ConvexPolygon {
+        ConvexPolygon { vertices }
+    }
+
+    /// Fan of triangles around the first vertex. Empty with fewer than three
+    /// vertices.
+    pub fn triangulate(&self) -> Vec<Triangle> {
+        fan(&self.vertices)
+    }
+
+    pub fn intersects_triangle(&self, t: &Triangle) -> bool {
+        self.triangulate().iter().any(|f| f.intersect(t))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(x: f64, y: f64, z: f64) -> Vertex {
+        Vertex::new(x, y, z)
+    }
+
+    #[test]
+    fn overlapping_quads() {
+        let square = Quad::new(
+            v(0.0, 0.0, 0.0),
+            v(2.0, 0.0, 0.0),
+            v(2.0, 2.0, 0.0),
+            v(0.0, 2.0, 0.0),
+        );
+
+        let [t1, t2] = square.triangulate();
+        assert_eq!(
+            t1.vertices(),
+            &[v(0.0, 0.0, 0.0), v(2.0, 0.0, 0.0), v(2.0, 2.0, 0.0)]
+        );
+        assert_eq!(
+            t2.vertices(),
+            &[v(0.0, 0.0, 0.0), v(2.0, 2.0, 0.0), v(0.0, 2.0, 0.0)]
+        );
+
+        // Upright through the square's middle, and stacked above it
+        let upright = Quad::new(
+            v(1.0, -1.0, -1.0),
+            v(1.0, 3.0, -1.0),
+            v(1.0, 3.0, 1.0),
+            v(1.0, -1.0, 1.0),
+        );
+        let shifted = Quad::new(
+            v(1.0, 1.0, 0.0),
+            v(3.0, 1.0, 0.0),
+            v(3.0, 3.0, 0.0),
+            v(1.0, 3.0, 0.0),
+        );
+        let above = Quad::new(
+            v(0.0, 0.0, 1.0),
+            v(2.0, 0.0, 1.0),
+            v(2.0, 2.0, 1.0),
+            v(0.0, 2.0, 1.0),
+        );
+
+        assert!(square.intersect(&upright) && upright.intersect(&square));
+        assert!(square.intersect(&shifted) && shifted.intersect(&square));
+        assert!(!square.intersect(&above) && !above.intersect(&square));
+    }
+
+    #[test]
+    fn pentagon_vs_triangle() {
+        // A regular pentagon of circumradius 1 in the z = 0 plane
+        let pentagon = ConvexPolygon::new(
+            (0..5)
+                .map(|k| {
+                    let a = std::f64::consts::TAU * k as f64 / 5.0;
+                    v(a.cos(), a.sin(), 0.0)
+                })
+                .collect(),
+        );
+        assert_eq!(pentagon.triangulate().len(), 3);
+
+        let piercing = Triangle::new(v(0.1, -0.1, -1.0), v(0.1, -0.1, 1.0), v(0.3, 0.2, 0.0));
+        let coplanar = Triangle::new(v(0.5, 0.5, 0.0), v(2.0, 0.5, 0.0), v(0.5, 2.0, 0.0));
+        let above = Triangle::new(v(0.0, 0.0, 1.0), v(1.0, 0.0, 1.0), v(0.0, 1.0, 1.0));
+        let beside = Triangle::new(v(1.5, 1.5, -1.0), v(1.5, 1.5, 1.0), v(2.0, 1.0, 0.0));
+
+        assert!(pentagon.intersects_triangle(&piercing));
+        assert!(pentagon.intersects_triangle(&coplanar));
+        assert!(!pentagon.intersects_triangle(&above));
+        assert!(!pentagon.intersects_triangle(&beside));
+
+        assert!(ConvexPolygon::new(vec![v(0.0, 0.0, 0.0), v(1.0, 0.0, 0.0)])
+            .triangulate()
+            .is_empty());
+    }
+}
@@ -0,0 +1,247 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::bvh::Bvh;
+use crate::{Triangle, Vertex};
+
+/*
+ * Saved scenes start with this, then a little endian u64 triangle count, the
+ * triangles as nine f64 each, and the hierarchy as Bvh::write lays it out.
+ */
+const MAGIC: &[u8; 8] = b"TRISCN01";
+
+/// Triangles added one at a time, each checked against those already present.
+///
+/// The stored triangles' bounding boxes are kept in a balanced hierarchy, so
+/// an insert visits O(log n) boxes plus those near the new triangle, and
+/// only the triangles whose boxes overlap get the exact test.
+#[derive(Default)]
+pub struct Scene {
+    triangles: Vec<Triangle>,
+    bvh: Bvh,
+}
+
+impl Scene {
+    pub fn new() -> Scene {
+        Scene::default()
+    }
+
+    /// Add `t`, returning the indices of the triangles already in the scene
+    /// that it intersects, in increasing order. `t` gets the next index.
+    pub fn insert(&mut self, t: Triangle) -> Vec<usize> {
+        let hits = self.intersecting(&t);
+
+        self.bvh.insert(t.aabb(), self.triangles.len());
+        self.triangles.push(t);
+
+        hits
+    }
+
+    /// The indices of the triangles in the scene that `t` intersects, in
+    /// increasing order, without adding `t`.
+    pub fn intersecting(&self, t: &Triangle) -> Vec<usize> {
+        let mut hits: Vec<usize> = self
+            .bvh
+            .query(&t.aabb())
+            .into_iter()
+            .filter(|&i| self.triangles[i].intersect(t))
+            .collect();
+        hits.sort_unstable();
+
+        hits
+    }
+
+    /// Write the triangles and their hierarchy to `path`, so a large static
+    /// scene can be built once and reloaded with `load`. Coordinates are
+    /// stored exactly.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(MAGIC)?;
+
+        out.write_all(&(self.triangles.len() as u64).to_le_bytes())?;
+        for c in self
+            .triangles
+            .iter()
+            .flat_map(|t| t.vertices())
+            .flat_map(|v| v.to_array())
+        {
+            out.write_all(&c.to_le_bytes())?;
+        }
+
+        self.bvh.write(&mut out)?;
+        out.flush()
+    }
+
+    /// Read a scene written by `save`. It answers `insert` and
+    /// `intersecting` exactly as the saved one would.
+    pub fn load(path: &Path) -> io::Result<Scene> {
+        let mut r = BufReader::new(File::open(path)?);
+
+        let mut magic = [0; 8];
+        r.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Scene: not a saved scene",
+            ));
+        }
+
+        let mut bytes = [0; 8];
+        r.read_exact(&mut bytes)?;
+        let count = u64::from_le_bytes(bytes) as usize;
+
+        let mut triangles = Vec::with_capacity(count.min(1 << 20));
+        for _ in 0..count {
+            let mut c = [0.0; 9];
+            for c in &mut c {
+                r.read_exact(&mut bytes)?;
+                *c = f64::from_le_bytes(bytes);
+            }
+            let v = |i: usize| Vertex::new(c[3 * i], c[3 * i + 1], c[3 * i + 2]);
+            triangles.push(Triangle::new(v(0), v(1), v(2)));
+        }
+
+        let bvh = Bvh::read(&mut r, triangles.len())?;
+        Ok(Scene { triangles, bvh })
+    }
+
+    pub fn triangles(&self) -> &[Triangle] {
+        &self.triangles
+    }
+
+    pub fn len(&self) -> usize {
+        self.triangles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{find_intersecting_pairs, triangle};
+
+    #[test]
+    fn insert_reports_collisions() {
+        let mut scene = Scene::new();
+        assert!(scene.is_empty());
+
+        assert_eq!(
+            scene.insert(triangle![[0, 0, 0], [1, 0, 0], [0, 1, 0]]),
+            vec![]
+        );
+        assert_eq!(
+            scene.insert(triangle![[5, 5, 5], [6, 5, 5], [5, 6, 5]]),
+            vec![]
+        );
+        assert_eq!(
+            scene.insert(triangle![[0.2, 0.2, -1], [0.2, 0.2, 1], [0.6, 0.1, 0]]),
+            vec![0]
+        );
+        // In the plane x = y, passing through all three
+        assert_eq!(
+            scene.insert(triangle![[0, 0, -1], [6, 6, 6], [0.3, 0.3, 6]]),
+            vec![0, 1, 2]
+        );
+        assert_eq!(scene.len(), 4);
+    }
+
+    #[test]
+    fn matches_batch_pairs() {
+        // A fan of long thin triangles crossing each other at different angles
+        let tris: Vec<Triangle> = (0..60)
+            .map(|i| {
+                let a = i as f64 * 0.37;
+                let (c, s) = (a.cos(), a.sin());
+                triangle![
+                    [3.0 * c, 3.0 * s, -0.5 + 0.01 * i as f64],
+                    [-3.0 * c, -3.0 * s, 0.5],
+                    [0.1 * i as f64, 0.2, 1]
+                ]
+            })
+            .collect();
+
+        let mut scene = Scene::new();
+        let mut pairs = Vec::new();
+        for (j, t) in tris.iter().enumerate() {
+            pairs.extend(scene.insert(t.clone()).into_iter().map(|i| (i, j)));
+        }
+        pairs.sort_unstable();
+
+        assert_eq!(pairs, find_intersecting_pairs(&tris));
+        assert!(!pairs.is_empty());
+    }
+
+    #[test]
+    fn save_and_load() {
+        let tris: Vec<Triangle> = (0..200)
+            .map(|i| {
+                let a = i as f64 * 0.61;
+                let (c, s) = (a.cos(), a.sin());
+                triangle![
+                    [5.0 * c, 5.0 * s, 0.01 * i as f64],
+                    [-c, -s, 1],
+                    [0.03 * i as f64, 0.1, -1]
+                ]
+            })
+            .collect();
+        let (first, rest) = tris.split_at(150);
+
+        let mut scene = Scene::new();
+        for t in first {
+            scene.insert(t.clone());
+        }
+
+        let path = std::env::temp_dir().join(format!(
+            "triangle-intersect-{}-scene.bin",
+            std::process::id()
+        ));
+        scene.save(&path).unwrap();
+        let mut loaded = Scene::load(&path).unwrap();
+
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[0] = b'X';
+        std::fs::write(&path, &bytes).unwrap();
+        assert!(Scene::load(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.len(), scene.len());
+        for (a, b) in loaded.triangles().iter().zip(scene.triangles()) {
+            assert_eq!(a.vertices(), b.vertices());
+        }
+
+        // Same answers, and they stay the same as both grow
+        let mut hits = 0;
+        for t in rest {
+            assert_eq!(loaded.intersecting(t), scene.intersecting(t));
+            let found = scene.insert(t.clone());
+            assert_eq!(loaded.insert(t.clone()), found);
+            hits += found.len();
+        }
+        assert!(hits > 0);
+    }
+
+    #[test]
+    fn load_rejects_truncated_files() {
+        let mut scene = Scene::new();
+        for i in 0..20 {
+            let x = i as f64;
+            scene.insert(triangle![[x, 0, 0], [x + 1.5, 0, 0], [x, 1, 1]]);
+        }
+
+        let path = std::env::temp_dir().join(format!(
+            "triangle-intersect-{}-truncated.bin",
+            std::process::id()
+        ));
+        scene.save(&path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        for len in [0, 8, 20, bytes.len() / 2, bytes.len() - 1] {
+            std::fs::write(&path, &bytes[..len]).unwrap();
+            assert!(Scene::load(&path).is_err(), "{} bytes", len);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+}
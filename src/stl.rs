@@ -0,0 +1,178 @@
+/*
+ * Binary STL: an 80 byte header, a little endian u32 triangle count, then
+ * per triangle a normal and three vertices as f32 and a u16 attribute.
+ * Coordinates go through f32, so they round trip only to single precision.
+ *
+ * Link: https://en.wikipedia.org/wiki/STL_(file_format)#Binary
+ */
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::{canonicalize_pairs, Scene, Triangle, Vertex};
+
+fn write_vertex(out: &mut impl Write, v: &Vertex) -> io::Result<()> {
+    for c in [v.x, v.y, v.z] {
+        out.write_all(&(c as f32).to_le_bytes())?;
+    }
+
+    Ok(())
+}
+
+/// Write `tris` to `path` as binary STL, with unit face normals (zero for
+/// degenerate triangles).
+pub fn write_stl(tris: &[Triangle], path: &Path) -> io::Result<()> {
+    let count = u32::try_from(tris.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "STL: too many triangles"))?;
+
+    let mut out = BufWriter::new(File::create(path)?);
+    out.write_all(&[0; 80])?;
+    out.write_all(&count.to_le_bytes())?;
+
+    for t in tris {
+        let normal = if t.is_degenerate() {
+            Vertex::new(0.0, 0.0, 0.0)
+        } else {
+            t.plane_equation().0
+        };
+
+        write_vertex(&mut out, &normal)?;
+        for v in t.vertices() {
+            write_vertex(&mut out, v)?;
+        }
+        out.write_all(&[0; 2])?;
+    }
+
+    out.flush()
+}
+
+/* Skip the header and return the triangle count */
+fn read_header(r: &mut impl Read) -> io::Result<u32> {
+    let mut header = [0; 84];
+    r.read_exact(&mut header)?;
+
+    Ok(u32::from_le_bytes([
+        header[80], header[81], header[82], header[83],
+    ]))
+}
+
+/* Read one triangle record, ignoring the stored normal */
+fn read_triangle(r: &mut impl Read) -> io::Result<Triangle> {
+    let mut record = [0; 50];
+    r.read_exact(&mut record)?;
+
+    let c = |i: usize| {
+        let at = 12 + 4 * i;
+        f32::from_le_bytes([record[at], record[at + 1], record[at + 2], record[at + 3]]) as f64
+    };
+    let v = |i: usize| Vertex::new(c(3 * i), c(3 * i + 1), c(3 * i + 2));
+
+    Ok(Triangle::new(v(0), v(1), v(2)))
+}
+
+/// Read every triangle of a binary STL file.
+pub fn read_stl(path: &Path) -> io::Result<Vec<Triangle>> {
+    let mut r = BufReader::new(File::open(path)?);
+    let count = read_header(&mut r)?;
+
+    (0..count).map(|_| read_triangle(&mut r)).collect()
+}
+
+/// Intersecting pairs among the triangles of a binary STL file, found in the
+/// same pass that reads them: each triangle is checked against a `Scene` of
+/// those read so far. Same canonical pairs as `find_intersecting_pairs` on
+/// the result of `read_stl`, but the `Scene`'s hierarchy makes this about
+/// O(n log n) for typical meshes instead of O(n^2). Every triangle is kept
+/// for the exact tests, so peak memory is close to that of `read_stl`.
+pub fn stream_self_intersections(path: &Path) -> io::Result<Vec<(usize, usize)>> {
+    let mut r = BufReader::new(File::open(path)?);
+    let count = read_header(&mut r)?;
+
+    let mut scene = Scene::new();
+    let mut pairs = Vec::new();
+    for j in 0..count as usize {
+        let hits = scene.insert(read_triangle(&mut r)?);
+        pairs.extend(hits.into_iter().map(|i| (i, j)));
+    }
+
+    canonicalize_pairs(&mut pairs);
+    Ok(pairs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::find_intersecting_pairs;
+    use crate::jitter::XorShift;
+    use std::path::PathBuf;
+
+    /* A path in the temp directory that no other test uses */
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "triangle-intersect-{}-{}.stl",
+            std::process::id(),
+            name
+        ))
+    }
+
+    #[test]
+    fn stream_matches_load_then_batch() {
+        // Small triangles scattered through a cube, so some cross
+        let mut rng = XorShift(0x1234_5678_9abc_def1);
+        let mut near =
+            |c: &Vertex| c.add(&Vertex::new(rng.unit(), rng.unit(), rng.unit()).scale(0.05));
+        let tris: Vec<Triangle> = (0..800)
+            .map(|_| {
+                let c = near(&Vertex::new(0.0, 0.0, 0.0)).scale(10.0);
+                Triangle::new(near(&c), near(&c), near(&c))
+            })
+            .collect();
+
+        let path = temp_path("stream");
+        write_stl(&tris, &path).unwrap();
+        let streamed = stream_self_intersections(&path).unwrap();
+        let batch = find_intersecting_pairs(&read_stl(&path).unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(streamed, batch);
+        assert!(!streamed.is_empty());
+    }
+
+    #[test]
+    fn write_read_round_trip() {
+        let tris = [
+            Triangle::new(
+                Vertex::new(0.0, 0.0, 0.0),
+                Vertex::new(2.0, 0.0, 0.0),
+                Vertex::new(0.0, 2.0, 0.0),
+            ),
+            Triangle::new(
+                Vertex::new(0.5, -1.25, 3.0),
+                Vertex::new(0.5, 4.0, 3.0),
+                Vertex::new(0.5, 0.0, -8.0),
+            ),
+        ];
+
+        let path = temp_path("round-trip");
+        write_stl(&tris, &path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        let read = read_stl(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(bytes.len(), 84 + 50 * tris.len());
+        assert_eq!(bytes[80..84], 2u32.to_le_bytes());
+
+        let normal = |record: usize, i: usize| {
+            let at = 84 + 50 * record + 4 * i;
+            f32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+        };
+        assert_eq!([normal(0, 0), normal(0, 1), normal(0, 2)], [0.0, 0.0, 1.0]);
+        assert_eq!([normal(1, 0), normal(1, 1), normal(1, 2)], [-1.0, 0.0, 0.0]);
+
+        assert_eq!(read.len(), tris.len());
+        for (a, b) in read.iter().zip(&tris) {
+            assert_eq!(a.vertices(), b.vertices());
+        }
+    }
+}
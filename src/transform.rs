@@ -0,0 +1,156 @@
+use crate::{Mesh, Triangle, Vertex};
+
+/// Rotation as a unit quaternion w + xi + yj + zk.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quat {
+    pub w: f64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Quat {
+    /// Rotation by `angle` radians about `axis`, counterclockwise when looking
+    /// down the axis towards the origin. `axis` need not be unit length.
+    pub fn from_axis_angle(axis: &Vertex, angle: f64) -> Quat {
+        let (sin, cos) = (angle / 2.0).sin_cos();
+        let a = axis.scale(sin / axis.length());
+
+        Quat {
+            w: cos,
+            x: a.x,
+            y: a.y,
+            z: a.z,
+        }
+    }
+
+    /*
+     * v' = v + 2w(q X v) + 2(q X (q X v)) for the vector part q
+     *
+     * Link: https://en.wikipedia.org/wiki/Quaternions_and_spatial_rotation
+     */
+    pub fn rotate_vertex(&self, v: &Vertex) -> Vertex {
+        let q = Vertex::new(self.x, self.y, self.z);
+        let t = q.cross_product(v).scale(2.0);
+
+        v.add(&t.scale(self.w)).add(&q.cross_product(&t))
+    }
+}
+
+/// Affine transform: v' = matrix * v + translation, with `matrix` in row
+/// major order.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    pub matrix: [[f64; 3]; 3],
+    pub translation: Vertex,
+}
+
+impl Transform {
+    pub fn new(matrix: [[f64; 3]; 3], translation: Vertex) -> Transform {
+        Transform {
+            matrix,
+            translation,
+        }
+    }
+
+    pub fn identity() -> Transform {
+        Transform::translation(Vertex::new(0.0, 0.0, 0.0))
+    }
+
+    pub fn translation(offset: Vertex) -> Transform {
+        Transform::new([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]], offset)
+    }
+
+    /// The rotation `q` as a matrix, without translation.
+    pub fn rotation(q: &Quat) -> Transform {
+        // Columns are the rotated basis vectors
+        let [x, y, z] = [
+            Vertex::new(1.0, 0.0, 0.0),
+            Vertex::new(0.0, 1.0, 0.0),
+            Vertex::new(0.0, 0.0, 1.0),
+        ]
+        .map(|e| q.rotate_vertex(&e));
+
+        Transform::new(
+            [[x.x, y.x, z.x], [x.y, y.y, z.y], [x.z, y.z, z.z]],
+            Vertex::new(0.0, 0.0, 0.0),
+        )
+    }
+
+    pub fn apply(&self, v: &Vertex) -> Vertex {
+        let row = |r: [f64; 3]| r[0] * v.x + r[1] * v.y + r[2] * v.z;
+        let [x, y, z] = self.matrix.map(row);
+
+        Vertex::new(x, y, z).add(&self.translation)
+    }
+}
+
+impl Mesh {
+    /// Apply `m` to every vertex in place. The faces are unchanged.
+    pub fn transform(&mut self, m: &Transform) {
+        for v in &mut self.vertices {
+            *v = m.apply(v);
+        }
+    }
+}
+
+impl Triangle {
+    /// This triangle rotated by `q` about the origin.
+    pub fn rotated(&self, q: &Quat) -> Triangle {
+        Triangle::from(self.vertices.map(|v| q.rotate_vertex(&v)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::triangle;
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn quarter_turn_about_z() {
+        let q = Quat::from_axis_angle(&Vertex::new(0.0, 0.0, 2.0), FRAC_PI_2);
+        let t = triangle![[1, 0, 0], [0, 1, 0], [1, 1, 5]];
+
+        let expected = [
+            Vertex::new(0.0, 1.0, 0.0),
+            Vertex::new(-1.0, 0.0, 0.0),
+            Vertex::new(-1.0, 1.0, 5.0),
+        ];
+        for (v, e) in t.rotated(&q).vertices().iter().zip(&expected) {
+            assert!(v.abs_diff_eq(e, 1e-12), "{:?} {:?}", v, e);
+        }
+
+        // The matrix form agrees with rotating directly
+        let m = Transform::rotation(&q);
+        for v in t.vertices() {
+            assert!(m.apply(v).abs_diff_eq(&q.rotate_vertex(v), 1e-12));
+        }
+    }
+
+    #[test]
+    fn transform_moves_mesh_vertices() {
+        let mut mesh = Mesh::new(
+            vec![
+                Vertex::new(0.0, 0.0, 0.0),
+                Vertex::new(1.0, 0.0, 0.0),
+                Vertex::new(0.0, 1.0, 0.0),
+            ],
+            vec![[0, 1, 2]],
+        );
+
+        // Double x, then shift
+        let m = Transform::new(
+            [[2.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            Vertex::new(1.0, 2.0, 3.0),
+        );
+        mesh.transform(&m);
+
+        assert_eq!(mesh.vertices[1], Vertex::new(3.0, 2.0, 3.0));
+        assert_eq!(mesh.vertices[2], Vertex::new(1.0, 3.0, 3.0));
+        assert_eq!(mesh.faces, vec![[0, 1, 2]]);
+
+        mesh.transform(&Transform::identity());
+        assert_eq!(mesh.vertices[0], Vertex::new(1.0, 2.0, 3.0));
+    }
+}
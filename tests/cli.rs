@@ -0,0 +1,155 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/* Run the binary with `args`, feeding it `input` on stdin */
+fn run(args: &[&str], input: &[u8]) -> Output {
+    run_with_env(args, &[], input)
+}
+
+/* `run` with only the given TRI_A and TRI_B style variables set */
+fn run_with_env(args: &[&str], vars: &[(&str, &str)], input: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_triangle-intersect"))
+        .args(args)
+        .env_remove("TRI_A")
+        .env_remove("TRI_B")
+        .envs(vars.iter().copied())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // The binary may exit before reading everything, so ignore broken pipes
+    let _ = child.stdin.take().unwrap().write_all(input);
+    child.wait_with_output().unwrap()
+}
+
+const CROSSING: &[u8] = b"0 0 0 1 0 0 0 1 0\n0.2 0.2 -1 0.2 0.2 1 1 1 1\n";
+const APART: &[u8] = b"0 0 0 1 0 0 0 1 0\n0 0 5 1 0 5 0 1 5\n";
+
+fn assert_silent_exit(output: &Output, code: i32) {
+    assert_eq!(output.status.code(), Some(code));
+    assert!(output.stdout.is_empty(), "{:?}", output);
+    assert!(output.stderr.is_empty(), "{:?}", output);
+}
+
+#[test]
+fn exit_code_reports_only_through_status() {
+    assert_silent_exit(&run(&["--exit-code"], CROSSING), 0);
+    assert_silent_exit(&run(&["--exit-code"], APART), 1);
+    assert_silent_exit(&run(&["--exit-code"], b"1 2\n"), 2);
+    assert_silent_exit(&run(&["--exit-code"], b"0 0 0\n"), 2);
+}
+
+#[test]
+fn unreadable_input_is_an_error() {
+    assert_silent_exit(&run(&["--exit-code"], b"\xff\n"), 2);
+
+    let output = run(&[], b"\xff\n");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(!output.stderr.is_empty());
+
+    let output = run(&["--labeled"], b"\xff\n");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(!output.stderr.is_empty());
+}
+
+#[test]
+fn bad_arguments() {
+    assert_silent_exit(&run(&["--exit-code", "--bogus"], b""), 2);
+    assert_silent_exit(&run(&["--exit-code", "--labeled"], b""), 2);
+
+    let output = run(&["--bogus"], b"");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Usage"));
+}
+
+#[test]
+fn rejected_lines_are_reported() {
+    let input =
+        b"0 0 0 1 0 0\nx 1\n0 1 0\n0.2 0.2 -1 0.2 0.2 1 1 1 1 7\n0.2 0.2 -1\n0.2 0.2 1 1 1 1\n";
+    let output = run(&[], input);
+    assert_eq!(output.status.code(), Some(0));
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.ends_with("Do the two triangles intersect?: yes\n"),
+        "{}",
+        stdout
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let lines: Vec<&str> = stderr.lines().collect();
+    assert_eq!(lines.len(), 2, "{}", stderr);
+    assert!(lines[0].contains("Invalid input: x 1"));
+    assert!(lines[1].contains("Too many values: 10 given, 9 still needed"));
+}
+
+#[test]
+fn scale_keeps_the_answer() {
+    for (input, answer) in [(CROSSING, "yes"), (APART, "no")] {
+        let output = run(&["--scale", "25.4"], input);
+        assert_eq!(output.status.code(), Some(0));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            stdout.ends_with(&format!("intersect?: {}\n", answer)),
+            "{}",
+            stdout
+        );
+
+        let code = if answer == "yes" { 0 } else { 1 };
+        assert_silent_exit(&run(&["--scale", "25.4", "--exit-code"], input), code);
+        assert_silent_exit(&run(&["--scale", "-0.001", "--exit-code"], input), code);
+    }
+
+    assert_silent_exit(&run(&["--scale", "0", "--exit-code"], CROSSING), 2);
+    let output = run(&["--scale", "0"], CROSSING);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--scale"));
+}
+
+#[test]
+fn triangles_from_env() {
+    let a = ("TRI_A", "0 0 0 1 0 0 0 1 0");
+    let crossing = ("TRI_B", "0.2 0.2 -1 0.2 0.2 1 1 1 1");
+    let apart = ("TRI_B", "0 0 5 1 0 5 0 1 5");
+
+    // stdin is ignored, so no prompts either
+    for (b, answer) in [(crossing, "yes\n"), (apart, "no\n")] {
+        let output = run_with_env(&[], &[a, b], APART);
+        assert_eq!(output.status.code(), Some(0));
+        assert_eq!(String::from_utf8_lossy(&output.stdout), answer);
+        assert!(output.stderr.is_empty());
+    }
+    assert_silent_exit(&run_with_env(&["--exit-code"], &[a, crossing], b""), 0);
+    assert_silent_exit(&run_with_env(&["--exit-code"], &[a, apart], b""), 1);
+
+    for vars in [vec![a], vec![crossing], vec![a, ("TRI_B", "1 2 3")]] {
+        let output = run_with_env(&[], &vars, CROSSING);
+        assert_eq!(output.status.code(), Some(2), "{:?}", vars);
+        assert!(output.stdout.is_empty());
+        assert!(!output.stderr.is_empty());
+    }
+}
+
+#[test]
+fn labeled_pairs() {
+    let input = b"0 0 0 1 0 0 0 1 0 0.2 0.2 -1 0.2 0.2 1 1 1 1\n\
+                  0 0 0 1 0 0 0 1 0 0 0 5 1 0 5 0 1 5\n\
+                  \n\
+                  1 2 3\n\
+                  0 0 0 1 0 0 0 1 0 0 0 0 -1 0 -1 0 -1 -1\n";
+    let output = run(&["--labeled"], input);
+    assert_eq!(output.status.code(), Some(0));
+
+    // The blank line is skipped, but the short line still takes index 2
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "pair 0: yes\npair 1: no\npair 3: yes\n"
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "pair 2: expected 18 coordinates\n"
+    );
+}